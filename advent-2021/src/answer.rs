@@ -0,0 +1,76 @@
+use crate::DayError;
+use std::convert::TryFrom;
+use std::fmt;
+
+// The answer to one part of a day.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Answer {
+    Integer(i128),
+    Text(String),
+    // A picture to be read by eye, one String per row.
+    Grid(Vec<String>),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Integer(value) => write!(f, "{}", value),
+            Answer::Text(text) => write!(f, "{}", text),
+            Answer::Grid(rows) => write!(f, "{}", rows.join("\n")),
+        }
+    }
+}
+
+macro_rules! answer_from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Self {
+                    Answer::Integer(value as i128)
+                }
+            }
+        )*
+    };
+}
+
+answer_from_integer!(i32, i64, i128, u32, u64, usize);
+
+// Anything past i128::MAX can't be held, though no day gets near it.
+impl TryFrom<u128> for Answer {
+    type Error = DayError;
+
+    fn try_from(value: u128) -> Result<Self, Self::Error> {
+        i128::try_from(value)
+            .map(Answer::Integer)
+            .map_err(|_| DayError::new(format!("answer {} is too large", value)))
+    }
+}
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Answer::Text(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Self {
+        Answer::Text(String::from(text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_answers() {
+        assert_eq!(Answer::from(-12i64).to_string(), "-12");
+        assert_eq!(Answer::from(7usize), Answer::Integer(7));
+        assert_eq!(Answer::try_from(u128::MAX >> 1).unwrap(), Answer::Integer(i128::MAX));
+        assert!(Answer::try_from(u128::MAX).is_err());
+
+        let grid = Answer::Grid(vec![String::from("█ █"), String::from(" █ ")]);
+        assert_eq!(grid.to_string(), "█ █\n █ ");
+        assert_eq!(Answer::from("ABC").to_string(), "ABC");
+    }
+}
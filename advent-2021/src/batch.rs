@@ -0,0 +1,270 @@
+use crate::{pretty_duration, run_day_limited, Answer, Answers, DayError, DayResult, DaySolver, InputSource};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+// One of a day's inputs in a batch directory, kept as `dayN/<name>.dat`, with its answers in
+// `dayN/<name>.answers` if they're known.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchInput {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+impl BatchInput {
+    fn answers_path(&self) -> PathBuf {
+        self.path.with_extension("answers")
+    }
+
+    // The input as named in reports, relative to the batch directory.
+    fn label(&self, day: usize) -> String {
+        format!("day{}/{}.dat", day, self.name)
+    }
+}
+
+// The inputs for a day, by name. A day without a directory has none.
+pub fn batch_inputs(dir: &Path, day: usize) -> io::Result<Vec<BatchInput>> {
+    let day_dir = dir.join(format!("day{}", day));
+    if !day_dir.is_dir() {
+        return Ok(vec![]);
+    }
+    let mut inputs = vec![];
+    for entry in fs::read_dir(day_dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|e| e == "dat") {
+            if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
+                inputs.push(BatchInput {
+                    name: String::from(name),
+                    path: path.clone(),
+                });
+            }
+        }
+    }
+    inputs.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(inputs)
+}
+
+// What came of solving a day on one of its inputs.
+pub struct BatchResult {
+    pub day: usize,
+    pub input: BatchInput,
+    pub result: Result<DayResult, DayError>,
+    // From the input's answers file, if it has one.
+    pub expected: Option<Answers>,
+}
+
+impl BatchResult {
+    // Whether each part matched the answers file. None if there wasn't one or the day failed.
+    pub fn checked(&self) -> Option<(bool, bool)> {
+        Some(self.expected.as_ref()?.check(self.result.as_ref().ok()?))
+    }
+
+    pub fn passed(&self) -> bool {
+        self.result.is_ok() && self.checked().is_none_or(|(part1, part2)| part1 && part2)
+    }
+
+    fn cell(&self) -> String {
+        match &self.result {
+            Ok(solution) => {
+                let mut cell = format!(
+                    "{}, {}  {}",
+                    short_answer(&solution.part1),
+                    short_answer(&solution.part2),
+                    pretty_duration(solution.timings.total_us() as f64)
+                );
+                match self.checked() {
+                    Some((true, true)) => cell.push_str("  pass"),
+                    Some(_) => cell.push_str("  FAIL"),
+                    None => (),
+                }
+                cell
+            }
+            Err(e) if e.timed_out => String::from("timeout"),
+            Err(_) => String::from("error"),
+        }
+    }
+
+    // What went wrong, if anything, one line per problem.
+    fn problems(&self) -> Vec<String> {
+        let label = self.input.label(self.day);
+        let (solution, expected) = match (&self.result, &self.expected) {
+            (Err(e), _) => return vec![format!("{}: {}", label, e)],
+            (Ok(solution), Some(expected)) => (solution, expected),
+            (Ok(_), None) => return vec![],
+        };
+        let mut problems = vec![];
+        for (part, expected, found) in [(1, &expected.part1, &solution.part1), (2, &expected.part2, &solution.part2)] {
+            if let Some(expected) = expected.as_ref().filter(|e| *e != found) {
+                problems.push(format!("{}: part {} expected {}, got {}", label, part, expected, found));
+            }
+        }
+        problems
+    }
+}
+
+// Grids don't fit on one line, so are only described.
+fn short_answer(answer: &Answer) -> String {
+    match answer {
+        Answer::Grid(rows) => format!("{} row grid", rows.len()),
+        answer => answer.to_string(),
+    }
+}
+
+// Solve a day on one of its inputs, and read the answers it should give. An answers file that
+// can't be read counts against the input.
+pub fn solve_batch(day: usize, solver: &'static dyn DaySolver, input: &BatchInput, timeout: Option<Duration>) -> BatchResult {
+    let answers_path = input.answers_path();
+    let expected = if answers_path.is_file() {
+        fs::read_to_string(&answers_path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| Answers::parse(&text).map_err(|e| e.to_string()))
+            .map(Some)
+            .map_err(|e| DayError::new(format!("{}: {}", answers_path.display(), e)).for_day(day))
+    } else {
+        Ok(None)
+    };
+    let source = InputSource::File(input.path.clone());
+    let (result, expected) = match expected {
+        Ok(expected) => (run_day_limited(day, solver, &source, timeout), expected),
+        Err(e) => (Err(e), None),
+    };
+    BatchResult {
+        day,
+        input: input.clone(),
+        result,
+        expected,
+    }
+}
+
+// A table with a row for each day and a column for each input name, followed by every failure
+// and wrong answer. Days without a particular input show a dash.
+pub fn batch_table(results: &[BatchResult]) -> String {
+    let mut names = results.iter().map(|r| r.input.name.as_str()).collect::<Vec<&str>>();
+    names.sort_unstable();
+    names.dedup();
+    let mut days = results.iter().map(|r| r.day).collect::<Vec<usize>>();
+    days.dedup();
+
+    let cell = |day: usize, name: &str| {
+        results
+            .iter()
+            .find(|r| r.day == day && r.input.name == name)
+            .map_or_else(|| String::from("-"), BatchResult::cell)
+    };
+    let widths = names
+        .iter()
+        .map(|&name| {
+            days.iter()
+                .map(|&day| cell(day, name).chars().count())
+                .chain([name.chars().count()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect::<Vec<usize>>();
+
+    let mut lines = vec![];
+    let header = names.iter().zip(&widths).map(|(name, &width)| format!("{:<width$}", name, width = width));
+    lines.push(format!("Day  {}", header.collect::<Vec<String>>().join("  ")).trim_end().to_string());
+    for &day in &days {
+        let cells = names
+            .iter()
+            .zip(&widths)
+            .map(|(&name, &width)| format!("{:<width$}", cell(day, name), width = width));
+        lines.push(format!("{:>3}  {}", day, cells.collect::<Vec<String>>().join("  ")).trim_end().to_string());
+    }
+
+    let problems = results.iter().flat_map(BatchResult::problems).collect::<Vec<String>>();
+    if !problems.is_empty() {
+        lines.push(String::new());
+        lines.extend(problems);
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Metrics, PartResult, Solution, Timings};
+
+    // Answers with the sum of its input's numbers, and their count.
+    struct Summing;
+
+    impl Solution for Summing {
+        type Parsed = Vec<i64>;
+
+        fn bundled_input(&self) -> &'static str {
+            ""
+        }
+
+        fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+            crate::lines(input).map(|line| line.parse(line.text)).collect()
+        }
+
+        fn part1(&self, numbers: &Self::Parsed) -> Result<PartResult, DayError> {
+            Ok(PartResult::new(numbers.iter().sum::<i64>(), ""))
+        }
+
+        fn part2(&self, numbers: &Self::Parsed) -> Result<PartResult, DayError> {
+            Ok(PartResult::new(numbers.len(), ""))
+        }
+    }
+
+    #[test]
+    fn test_batch() {
+        let dir = std::env::temp_dir().join(format!("advent21-batch-{}", std::process::id()));
+        let day_dir = dir.join("day1");
+        fs::create_dir_all(&day_dir).unwrap();
+        fs::write(day_dir.join("bob.dat"), "1\n2\n3").unwrap();
+        fs::write(day_dir.join("bob.answers"), "part1 = 6\npart2 = 4").unwrap();
+        fs::write(day_dir.join("alice.dat"), "5\nx").unwrap();
+        fs::write(day_dir.join("carol.dat"), "10").unwrap();
+        fs::write(day_dir.join("carol.answers"), "part1 = 10").unwrap();
+        fs::write(day_dir.join("notes.txt"), "not an input").unwrap();
+
+        let inputs = batch_inputs(&dir, 1).unwrap();
+        assert_eq!(inputs.iter().map(|i| i.name.as_str()).collect::<Vec<&str>>(), vec!["alice", "bob", "carol"]);
+        assert!(batch_inputs(&dir, 2).unwrap().is_empty());
+
+        let results = inputs.iter().map(|input| solve_batch(1, &Summing, input, None)).collect::<Vec<BatchResult>>();
+        assert_eq!(results.iter().map(BatchResult::passed).collect::<Vec<bool>>(), vec![false, false, true]);
+        assert_eq!(results[1].checked(), Some((true, false)));
+        assert_eq!(results[2].checked(), Some((true, true)));
+
+        let table = batch_table(&results);
+        let lines = table.lines().collect::<Vec<&str>>();
+        assert!(lines[0].starts_with("Day  alice  bob"));
+        assert!(lines[1].starts_with("  1  error  6, 3  "));
+        assert!(lines[1].contains("us  FAIL  10, 1  "));
+        assert!(lines[1].ends_with("us  pass"));
+        assert_eq!(lines[3], "day1/alice.dat: Day 1 (line 2, column 1): could not parse 'x'");
+        assert_eq!(lines[4], "day1/bob.dat: part 2 expected 4, got 3");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_table_gaps() {
+        let solved = |day: usize, name: &str| BatchResult {
+            day,
+            input: BatchInput {
+                name: String::from(name),
+                path: PathBuf::from(format!("day{}/{}.dat", day, name)),
+            },
+            result: Ok(DayResult {
+                part1: Answer::Integer(1),
+                part2: Answer::Grid(vec![String::from("#"), String::from(".")]),
+                description: String::new(),
+                timings: Timings::default(),
+                metrics: Metrics::default(),
+                memory: None,
+            }),
+            expected: None,
+        };
+        let table = batch_table(&[solved(13, "alice"), solved(14, "bob")]);
+        assert_eq!(
+            table,
+            "Day  alice                 bob\n 13  1, 2 row grid  0.0us  -\n 14  -                     1, 2 row grid  0.0us"
+        );
+    }
+}
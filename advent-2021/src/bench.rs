@@ -0,0 +1,174 @@
+use crate::{DayError, DaySolver};
+use std::time::{Duration, Instant};
+
+// How many times to run each day when benchmarking.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchConfig {
+    // Runs made and thrown away before any are measured.
+    pub warmup: usize,
+    pub runs: usize,
+    // If set, keep running until this much time has passed, however many runs that takes.
+    pub budget: Option<Duration>,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self {
+            warmup: 3,
+            runs: 10,
+            budget: None,
+        }
+    }
+}
+
+// Summary of a set of timings, all in microseconds.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Stats {
+    pub min: f64,
+    pub median: f64,
+    pub mean: f64,
+    pub stddev: f64,
+}
+
+impl Stats {
+    pub fn from(samples: &[f64]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let n = sorted.len();
+        let median = if n.is_multiple_of(2) {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0
+        } else {
+            sorted[n / 2]
+        };
+        let mean = sorted.iter().sum::<f64>() / n as f64;
+        // The sample standard deviation, as the runs are a sample of all possible runs.
+        let stddev = if n > 1 {
+            (sorted.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (n - 1) as f64).sqrt()
+        } else {
+            0.0
+        };
+        Self {
+            min: sorted[0],
+            median,
+            mean,
+            stddev,
+        }
+    }
+}
+
+pub struct BenchResult {
+    pub runs: usize,
+    pub total: Stats,
+    pub parse: Stats,
+    pub part1: Stats,
+    pub part2: Stats,
+}
+
+// Solve the same input repeatedly. The first failure stops the benchmark.
+pub fn bench(solver: &dyn DaySolver, input: &str, config: &BenchConfig) -> Result<BenchResult, DayError> {
+    for _ in 0..config.warmup {
+        solver.solve(input)?;
+    }
+
+    let mut totals = vec![];
+    let mut parses = vec![];
+    let mut part1s = vec![];
+    let mut part2s = vec![];
+    let start = Instant::now();
+    loop {
+        let run_start = Instant::now();
+        let result = solver.solve(input)?;
+        totals.push(run_start.elapsed().as_nanos() as f64 / 1000.0);
+        parses.push(result.timings.parse_us as f64);
+        part1s.push(result.timings.part1_us as f64);
+        part2s.push(result.timings.part2_us as f64);
+
+        let done = match config.budget {
+            Some(budget) => start.elapsed() >= budget,
+            None => totals.len() >= config.runs,
+        };
+        if done {
+            break;
+        }
+    }
+
+    Ok(BenchResult {
+        runs: totals.len(),
+        total: Stats::from(&totals),
+        parse: Stats::from(&parses),
+        part1: Stats::from(&part1s),
+        part2: Stats::from(&part2s),
+    })
+}
+
+// A one line summary of a benchmarked day.
+pub fn bench_summary(day: usize, result: &BenchResult) -> String {
+    format!(
+        "Day {:>2} : {:>4} runs  min {:>9}  median {:>9}  mean {:>9}  stddev {:>9}  \
+         (mean parse {}, part 1 {}, part 2 {})",
+        day,
+        result.runs,
+        pretty_duration(result.total.min),
+        pretty_duration(result.total.median),
+        pretty_duration(result.total.mean),
+        pretty_duration(result.total.stddev),
+        pretty_duration(result.parse.mean),
+        pretty_duration(result.part1.mean),
+        pretty_duration(result.part2.mean),
+    )
+}
+
+// Format microseconds in the largest sensible unit, keeping a little precision.
+pub fn pretty_duration(micros: f64) -> String {
+    if micros >= 1_000_000.0 {
+        format!("{:.2}s", micros / 1_000_000.0)
+    } else if micros >= 1000.0 {
+        format!("{:.2}ms", micros / 1000.0)
+    } else {
+        format!("{:.1}us", micros)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats::from(&[4.0, 1.0, 3.0, 2.0]);
+        assert_eq!(stats.min, 1.0);
+        assert_eq!(stats.median, 2.5);
+        assert_eq!(stats.mean, 2.5);
+        assert!((stats.stddev - 1.29099).abs() < 1e-5);
+
+        let single = Stats::from(&[7.0]);
+        assert_eq!((single.median, single.stddev), (7.0, 0.0));
+        assert_eq!(Stats::from(&[]), Stats::default());
+    }
+
+    #[test]
+    fn test_pretty_duration() {
+        assert_eq!(pretty_duration(12.34), "12.3us");
+        assert_eq!(pretty_duration(1500.0), "1.50ms");
+        assert_eq!(pretty_duration(2_250_000.0), "2.25s");
+    }
+
+    #[test]
+    #[cfg(feature = "day1")]
+    fn test_bench_runs() {
+        let year = crate::year2021();
+        let day1 = year.get(1).unwrap();
+        let config = BenchConfig {
+            warmup: 1,
+            runs: 3,
+            budget: None,
+        };
+        let result = bench(day1, "1\n2\n3\n", &config).unwrap();
+        assert_eq!(result.runs, 3);
+        assert!(result.total.min <= result.total.median);
+        assert!(bench(day1, "x", &config).is_err());
+    }
+}
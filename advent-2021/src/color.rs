@@ -0,0 +1,33 @@
+use crate::Answer;
+use regex::Regex;
+use termion::{color, style};
+
+pub fn print_day(d: usize) {
+    println!(" \u{1F389} {}Day {} !{}", style::Underline, d, style::Reset);
+}
+
+pub fn fmt_bright<T: std::fmt::Display + ?Sized>(t: &T) -> String {
+    format!(
+        "{}{}{}",
+        color::Fg(color::LightWhite),
+        t,
+        color::Fg(color::Reset)
+    )
+}
+
+// Highlight the answers wherever they appear in the description. A grid is highlighted row by row.
+pub fn brighten(desc: &str, p1: &Answer, p2: &Answer) -> String {
+    let mut replaced = desc.to_string();
+    for answer in [p1, p2] {
+        let texts = match answer {
+            Answer::Grid(rows) => rows.clone(),
+            _ => vec![answer.to_string()],
+        };
+        for text in texts.iter().filter(|t| !t.trim().is_empty()) {
+            let re = Regex::new(&regex::escape(text)).unwrap();
+            let bright = fmt_bright(text);
+            replaced = re.replace_all(&replaced, regex::NoExpand(&bright)).to_string();
+        }
+    }
+    replaced
+}
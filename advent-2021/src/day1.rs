@@ -1,40 +1,65 @@
-use crate::{DaySolver, DayResult};
-use std::time::SystemTime;
+use crate::{parse_at, DayError, DayInfo, PartResult, Solution};
 
 pub struct Day {}
 
-impl DaySolver for Day {
-    fn solve(&self) -> DayResult {
-        let data = include_str!("data/day1.dat");
-        let values: Vec<i32> = data.lines().map(|c| c.parse::<i32>().unwrap()).collect();
-        let start = SystemTime::now();
-        let increases = increases(&values);
-        let smoothed_increases = smoothed(&values);
-        let timed = SystemTime::now().duration_since(start).unwrap();
-        let description = format!("Increases {} . Smoothed increases {} .", increases, smoothed_increases);
+pub const INFO: DayInfo = DayInfo { day: 1, title: "Sonar Sweep" };
 
-        DayResult {
-            description,
-            part1: format!("{}", increases),
-            part2: format!("{}", smoothed_increases),
-            timing_us: timed.as_micros(),
-        }
+impl Solution for Day {
+    type Parsed = Vec<i32>;
+
+    fn bundled_input(&self) -> &'static str {
+        include_str!("data/day1.dat")
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        readings(input)
+    }
+
+    fn part1(&self, values: &Self::Parsed) -> Result<PartResult, DayError> {
+        let increases = increases(values);
+        Ok(PartResult::new(increases, format!("Increases {} .", increases)))
+    }
+
+    fn part2(&self, values: &Self::Parsed) -> Result<PartResult, DayError> {
+        let smoothed_increases = smoothed(values);
+        Ok(PartResult::new(smoothed_increases, format!("Smoothed increases {} .", smoothed_increases)))
     }
 }
 
+// The tests have always sat above the helpers they exercise.
 #[cfg(test)]
+#[allow(clippy::items_after_test_module)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_data() {
         let data = include_str!("./data/test_day1.dat");
-        let vals: Vec<i32> = data.lines().map(|c| c.parse::<i32>().unwrap()).collect();
+        let vals = readings(data).unwrap();
         let increases = increases(&vals);
         let smoothed_increases = smoothed(&vals);
         assert_eq!(increases, 7);
         assert_eq!(smoothed_increases, 5);
     }
+
+    #[test]
+    fn test_bad_data() {
+        let err = readings("199\n2x0\n").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(2), Some(1)));
+        assert!(readings("").is_err());
+    }
+}
+
+fn readings(data: &str) -> Result<Vec<i32>, DayError> {
+    let values = data
+        .lines()
+        .enumerate()
+        .map(|(i, l)| parse_at::<i32>(l.trim(), i + 1, l))
+        .collect::<Result<Vec<i32>, DayError>>()?;
+    if values.is_empty() {
+        return Err(DayError::new("no depth readings"));
+    }
+    Ok(values)
 }
 
 fn increases(data: &[i32]) -> i32 {
@@ -68,4 +93,4 @@ fn smoothed(data: &[i32]) -> i32 {
     }
 
     increases
-}
\ No newline at end of file
+}
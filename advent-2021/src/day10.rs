@@ -1,45 +1,65 @@
-use std::time::SystemTime;
-use crate::{DayResult, DaySolver};
+use crate::{DayError, DayInfo, PartResult, Solution};
 
 pub struct Day {}
 
-impl DaySolver for Day {
-    fn solve(&self) -> DayResult {
-        let data = include_str!("data/day10.dat");
-        let start = SystemTime::now();
-        let (error_score, completion_score) = diagnose(data);
-        let timed = SystemTime::now().duration_since(start).unwrap();
-        let description = format!("Error score of bad lines {}, completion score {}",
-                                  error_score, completion_score);
-        DayResult{
-            description,
-            part1: format!("{}", error_score),
-            part2: format!("{}", completion_score),
-            timing_us: timed.as_micros(),
-        }
+pub const INFO: DayInfo = DayInfo { day: 10, title: "Syntax Scoring" };
+
+impl Solution for Day {
+    type Parsed = Vec<LineDiagnosis>;
+
+    fn bundled_input(&self) -> &'static str {
+        include_str!("data/day10.dat")
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        diagnose(input)
+    }
+
+    fn part1(&self, diagnoses: &Self::Parsed) -> Result<PartResult, DayError> {
+        let error_score = error_score(diagnoses);
+        Ok(PartResult::new(error_score, format!("Error score of bad lines {}", error_score)))
+    }
+
+    fn part2(&self, diagnoses: &Self::Parsed) -> Result<PartResult, DayError> {
+        let completion_score = completion_score(diagnoses)?;
+        Ok(PartResult::new(completion_score, format!("Completion score {}", completion_score)))
     }
 }
 
-enum LineDiagnosis {
+pub enum LineDiagnosis {
     LineError(u64),
     Incomplete(Vec<char>),
 }
 
-fn diagnose(data: &str) -> (u64, u64) {
-    let diagnoses = data.lines().map(diagnose_line);
-    let mut error_score = 0;
-    let mut completion_scores = vec![];
+fn diagnose(data: &str) -> Result<Vec<LineDiagnosis>, DayError> {
+    data.lines()
+        .enumerate()
+        .map(|(i, line)| diagnose_line(line).map_err(|e| e.on_line(i + 1)))
+        .collect()
+}
+
+fn error_score(diagnoses: &[LineDiagnosis]) -> u64 {
+    diagnoses.iter().map(|d| match d {
+        LineDiagnosis::LineError(v) => *v,
+        LineDiagnosis::Incomplete(_) => 0,
+    }).sum()
+}
 
-    for d in diagnoses {
-        match d {
-            LineDiagnosis::LineError(v) => error_score += v,
-            LineDiagnosis::Incomplete(v) => completion_scores.push(score_completion(&v)),
+fn completion_score(diagnoses: &[LineDiagnosis]) -> Result<u64, DayError> {
+    let mut completion_scores = vec![];
+    for diagnosis in diagnoses {
+        if let LineDiagnosis::Incomplete(v) = diagnosis {
+            completion_scores.push(score_completion(v));
         }
     }
 
+    if completion_scores.is_empty() {
+        return Err(DayError::new("no incomplete lines to score"));
+    }
+
     completion_scores.sort_unstable();
     // Rust round *up* in this case.
-    (error_score, completion_scores[(completion_scores.len()/2)])
+    Ok(completion_scores[completion_scores.len()/2])
 }
 
 fn score_completion(incomplete: &[char]) -> u64 {
@@ -57,21 +77,29 @@ fn score_completion(incomplete: &[char]) -> u64 {
     score
 }
 
-fn diagnose_line(line: &str) -> LineDiagnosis {
+fn diagnose_line(line: &str) -> Result<LineDiagnosis, DayError> {
     // We check char by char, keeping a stack.
     let mut stack = vec![];
-    for c in line.chars() {
-        match c {
-            '(' | '[' | '{' | '<' => stack.push(c),
-            ')' => if stack.pop().unwrap() != '(' { return LineDiagnosis::LineError(3); },
-            ']' => if stack.pop().unwrap() != '[' { return LineDiagnosis::LineError(57); },
-            '}' => if stack.pop().unwrap() != '{' { return LineDiagnosis::LineError(1197); },
-            '>' => if stack.pop().unwrap() != '<' { return LineDiagnosis::LineError(25137); },
-            _ => panic!("unexpected char {} in input", c),
+    for (i, c) in line.chars().enumerate() {
+        let (opener, score) = match c {
+            '(' | '[' | '{' | '<' => {
+                stack.push(c);
+                continue;
+            },
+            ')' => ('(', 3),
+            ']' => ('[', 57),
+            '}' => ('{', 1197),
+            '>' => ('<', 25137),
+            _ => return Err(DayError::new(format!("unexpected char '{}' in input", c)).at_column(i + 1)),
+        };
+        match stack.pop() {
+            Some(open) if open == opener => (),
+            Some(_) => return Ok(LineDiagnosis::LineError(score)),
+            None => return Err(DayError::new(format!("'{}' closes nothing", c)).at_column(i + 1)),
         }
     }
 
-    LineDiagnosis::Incomplete(stack)
+    Ok(LineDiagnosis::Incomplete(stack))
 }
 
 #[cfg(test)]
@@ -81,8 +109,16 @@ mod tests {
     #[test]
     fn test_data() {
         let data = include_str!("data/test_day10.dat");
-        let (error_score, completion_score) = diagnose(data);
-        assert_eq!(error_score, 26397);
-        assert_eq!(completion_score, 288957);
+        let diagnoses = diagnose(data).unwrap();
+        assert_eq!(error_score(&diagnoses), 26397);
+        assert_eq!(completion_score(&diagnoses).unwrap(), 288957);
+    }
+
+    #[test]
+    fn test_bad_data() {
+        let err = diagnose("[({(<(())[]>[[{[]{<()<>>\n[(()[<>])]({[<{<<a[]>>(").err().unwrap();
+        assert_eq!((err.line, err.column), (Some(2), Some(18)));
+        let err = diagnose("[]]").err().unwrap();
+        assert_eq!((err.line, err.column), (Some(1), Some(3)));
     }
 }
@@ -1,128 +1,151 @@
 use std::fmt::{Display, Formatter};
-use crate::{DaySolver, DayResult};
-use std::time::SystemTime;
+use crate::{checkpoint, trace, DayError, DayInfo, Frame, Grid, Level, PartResult, Progress, Solution, Visualize, SURROUNDING};
 
 pub struct Day {}
 
-impl DaySolver for Day {
-    fn solve(&self) -> DayResult {
-        let data = include_str!("data/day11.dat");
-        let start = SystemTime::now();
-        let mut grid = OctoGrid::new(data);
-        let flashed_100 = grid.step_n(100);
-        let mut grid2 = OctoGrid::new(data);
-        let synchronized_flash = grid2.first_synchronized(100);
-        let timed = SystemTime::now().duration_since(start).unwrap();
-        let description = format!("Octopus flashes after 100 steps : {} .\n\
-        All octopuses flashed at {}.", flashed_100, synchronized_flash);
-
-        DayResult {
-            description,
-            part1: format!("{}", flashed_100),
-            part2: format!("{}", synchronized_flash),
-            timing_us: timed.as_micros(),
+pub const INFO: DayInfo = DayInfo { day: 11, title: "Dumbo Octopus" };
+
+// Inputs synchronize within a few hundred steps. A grid that hasn't by this many is taken to be
+// one that never will, as some (such as "02") cycle forever without all flashing at once.
+const MAX_STEPS: usize = 10_000;
+
+impl Solution for Day {
+    type Parsed = OctoGrid;
+
+    fn bundled_input(&self) -> &'static str {
+        include_str!("data/day11.dat")
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        OctoGrid::new(input)
+    }
+
+    fn part1(&self, grid: &Self::Parsed) -> Result<PartResult, DayError> {
+        let flashed_100 = grid.clone().step_n(100);
+        Ok(PartResult::new(flashed_100, format!("Octopus flashes after 100 steps : {} .", flashed_100)))
+    }
+
+    fn part2(&self, grid: &Self::Parsed) -> Result<PartResult, DayError> {
+        let synchronized_flash = grid.clone().first_synchronized()?;
+        Ok(PartResult::new(synchronized_flash, format!("All octopuses flashed at {}.", synchronized_flash)))
+    }
+
+    fn visualizer(&self) -> Option<&dyn Visualize<Parsed = Self::Parsed>> {
+        Some(self)
+    }
+}
+
+// Each step until the octopuses all flash together.
+impl Visualize for Day {
+    fn frames(&self, grid: &OctoGrid) -> Result<Vec<Frame>, DayError> {
+        let mut grid = grid.clone();
+        let target = grid.octopuses.width() * grid.octopuses.height();
+        let mut frames = vec![Frame::new("Before any steps", &grid)];
+        let mut flashed = 0;
+        while flashed < target {
+            if frames.len() > MAX_STEPS {
+                return Err(never_synchronized());
+            }
+            flashed = grid.step();
+            frames.push(Frame::new(format!("Step {}: {} flashed", frames.len(), flashed), &grid));
         }
+        Ok(frames)
     }
 }
 
-struct OctoGrid {
-    octopuses: Vec<Vec<u8>>,
+fn never_synchronized() -> DayError {
+    DayError::new(format!("the octopuses don't all flash together within {} steps", MAX_STEPS))
+}
+
+#[derive(Clone)]
+pub struct OctoGrid {
+    octopuses: Grid<u8>,
 }
 
 impl Display for OctoGrid {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "{}", self.octopuses.iter()
-            .map(|r| format!("{:?}", r))
-            .collect::<Vec<String>>()
-            .join("\n"))
+        writeln!(f, "{}", self.octopuses)
     }
 }
 
 impl OctoGrid {
-    fn new(data: &str) -> Self {
-        let mut octopuses = vec![];
-        for row in data.lines() {
-            let octo_row = row.chars().map(|c| format!("{}", c).parse::<u8>().unwrap()).collect();
-            octopuses.push(octo_row);
-        }
-
-        OctoGrid {
+    fn new(data: &str) -> Result<Self, DayError> {
+        let octopuses = Grid::parse(data, |c| {
+            c.to_digit(10)
+                .map(|energy| energy as u8)
+                .ok_or_else(|| format!("expected an energy level, found '{}'", c))
+        })?;
+
+        Ok(OctoGrid {
             octopuses,
-        }
+        })
     }
 
     fn step_n(&mut self, n: usize) -> u64 {
         let mut flashes = 0u64;
-        for _ in 0..n {
+        for i in 0..n {
             let flashed = self.step();
-            //println!("{}", self);
-            //println!("[{}] : {} flashes", i, flashed);
+            trace!(Level::Trace, INFO.day, "\n{}", self);
+            trace!(Level::Debug, INFO.day, "[{}] : {} flashes", i, flashed);
             flashes += flashed as u64;
         }
         flashes
     }
 
-    fn first_synchronized(&mut self, target: u8) -> u64 {
-        let mut step = 0u64;
+    // The first step at which every octopus flashes.
+    fn first_synchronized(&mut self) -> Result<u64, DayError> {
+        let target = self.octopuses.width() * self.octopuses.height();
+        let mut step = 0;
         let mut flashed = 0;
         while flashed < target {
+            if step == MAX_STEPS {
+                return Err(never_synchronized());
+            }
+            checkpoint(Progress::count(step as u64, "steps"))?;
             step += 1;
             flashed = self.step();
-            //println!("Step {} => {} flashes", step, flashed);
+            trace!(Level::Debug, INFO.day, "Step {} => {} flashes", step, flashed);
         }
-        step
+        Ok(step as u64)
     }
 
     // Perform a single step, counting the number that flashed.
-    fn step(&mut self) -> u8 {
+    fn step(&mut self) -> usize {
         // First, increment everything and mark those going to flash.
         let mut to_flash = vec![];
-        for (i, r) in self.octopuses.iter_mut().enumerate() {
-            for (j, c) in r.iter_mut().enumerate() {
-                *c += 1;
-                if *c >= 10 {
-                    to_flash.push((i, j));
-                }
+        for pos in self.octopuses.positions() {
+            let c = &mut self.octopuses[pos];
+            *c += 1;
+            if *c >= 10 {
+                to_flash.push(pos);
             }
         }
 
         let mut flashed = vec![];
         // Now do the flashing.
-        while let Some((fi, fj)) = to_flash.pop() {
+        while let Some(pos) = to_flash.pop() {
             // Note that this flashed.
-            flashed.push((fi, fj));
-
-            // Increment neighbours and add any that flash.
-            for incr_i in -1..=1i8 {
-                for incr_j in -1..=1i8 {
-                    // Skip self flashing, and anything outside the grid.
-                    if (incr_i == 0 && incr_j == 0) ||
-                        (incr_i < 0 && fi == 0) ||
-                        (incr_i > 0 && fi + 1 >= self.octopuses.len()) ||
-                        (incr_j < 0 && fj == 0) ||
-                        (incr_j > 0 && fj + 1 >= self.octopuses[0].len())
-                    {
-                        continue;
-                    }
-
-                    // Otherwise, increase if possible, and note if it flashes.
-                    let upd_i = (fi as i8 + incr_i) as usize;
-                    let upd_j = (fj as i8 + incr_j) as usize;
-                    self.octopuses[upd_i][upd_j] += 1;
-                    let neighbour = self.octopuses[upd_i][upd_j];
-                    if neighbour == 10 {
-                        to_flash.push((upd_i, upd_j))
-                    }
+            flashed.push(pos);
+
+            // Increment neighbours, and note any that flash.
+            for &offset in &SURROUNDING {
+                let upd = match self.octopuses.step(pos, offset) {
+                    Some(upd) => upd,
+                    None => continue,
+                };
+                self.octopuses[upd] += 1;
+                if self.octopuses[upd] == 10 {
+                    to_flash.push(upd)
                 }
             }
         }
 
         // Reset everything that flashed to 0.
-        for (i, j) in &flashed {
-            self.octopuses[*i][*j] = 0;
+        for pos in &flashed {
+            self.octopuses[*pos] = 0;
         }
 
-        flashed.len() as u8
+        flashed.len()
     }
 }
 
@@ -133,12 +156,37 @@ mod tests {
     #[test]
     fn test_data() {
         let data = include_str!("data/test_day11.dat");
-        let mut grid = OctoGrid::new(data);
+        let mut grid = OctoGrid::new(data).unwrap();
         let flashed_100 = grid.step_n(100);
         assert_eq!(flashed_100, 1656);
-        let mut grid2 = OctoGrid::new(data);
-        let synchronized_flash = grid2.first_synchronized(100);
+        let mut grid2 = OctoGrid::new(data).unwrap();
+        let synchronized_flash = grid2.first_synchronized().unwrap();
         assert_eq!(synchronized_flash, 195);
     }
+
+    #[test]
+    fn test_never_synchronized() {
+        // The two flash in turn, forever.
+        let grid = OctoGrid::new("02").unwrap();
+        let err = grid.clone().first_synchronized().unwrap_err();
+        assert_eq!(err.reason, format!("the octopuses don't all flash together within {} steps", MAX_STEPS));
+        assert_eq!(Day {}.frames(&grid).unwrap_err(), err);
+    }
+
+    #[test]
+    fn test_frames() {
+        let grid = OctoGrid::new(include_str!("data/test_day11.dat")).unwrap();
+        let frames = Day {}.frames(&grid).unwrap();
+        assert_eq!(frames.len(), 196);
+        assert_eq!(frames[0].picture, grid.to_string());
+        assert_eq!(frames[195].caption, "Step 195: 100 flashed");
+    }
+
+    #[test]
+    fn test_bad_data() {
+        let err = OctoGrid::new("5483\n27-5").err().unwrap();
+        assert_eq!((err.line, err.column), (Some(2), Some(3)));
+        assert!(OctoGrid::new("5483\n274").is_err());
+    }
 }
 
@@ -1,30 +1,36 @@
-use crate::{DaySolver, DayResult};
-use std::time::SystemTime;
+use crate::{trace, DayError, DayInfo, Level, PartResult, Solution};
 use std::collections::HashMap;
 
 pub struct Day {}
 
-impl DaySolver for Day {
-    fn solve(&self) -> DayResult {
-        let data = include_str!("data/day12.dat");
-        let start = SystemTime::now();
-        let caves = Caves::from(data);
-        let routes1 = caves.routes(1);
-        let routes2 = caves.routes(2);
-        let timed = SystemTime::now().duration_since(start).unwrap();
-        let description = format!("Cave system has {} routes, or if allowing one double exploration {} routes", routes1, routes2);
-
-        DayResult {
-            description,
-            part1: format!("{}", routes1),
-            part2: format!("{}", routes2),
-            timing_us: timed.as_micros(),
-        }
+pub const INFO: DayInfo = DayInfo { day: 12, title: "Passage Pathing" };
+
+impl Solution for Day {
+    type Parsed = Caves;
+
+    fn bundled_input(&self) -> &'static str {
+        include_str!("data/day12.dat")
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        Caves::from(input)
+    }
+
+    fn part1(&self, caves: &Self::Parsed) -> Result<PartResult, DayError> {
+        let (routes1, visits) = caves.routes(1);
+        Ok(PartResult::new(routes1, format!("Cave system has {} routes", routes1))
+            .with_metric("nodes_visited", visits))
+    }
+
+    fn part2(&self, caves: &Self::Parsed) -> Result<PartResult, DayError> {
+        let (routes2, visits) = caves.routes(2);
+        Ok(PartResult::new(routes2, format!("If allowing one double exploration {} routes", routes2))
+            .with_metric("nodes_visited", visits))
     }
 }
 
 #[derive(Debug)]
-struct Caves {
+pub struct Caves {
     connections: Vec<Vec<usize>>,
     start: usize,
     end: usize,
@@ -33,15 +39,18 @@ struct Caves {
 }
 
 impl Caves {
-    fn from(data: &str) -> Self {
+    fn from(data: &str) -> Result<Self, DayError> {
         let mut connections: Vec<Vec<usize>> = vec![];
         let start = 0;
         let end = 1;
         let mut indices: HashMap<String, usize> = HashMap::new();
         indices.insert(String::from("start"),start);
         indices.insert(String::from("end"),end);
-        for line in data.lines() {
+        for (i, line) in data.lines().enumerate() {
             let caves = line.split('-').map(String::from).collect::<Vec<String>>();
+            if caves.len() != 2 || caves.iter().any(|c| c.is_empty()) {
+                return Err(DayError::at(i + 1, 1, format!("expected 'cave-cave', found '{}'", line)));
+            }
             let from = caves[0].clone();
             let to = caves[1].clone();
 
@@ -66,13 +75,17 @@ impl Caves {
             large[*i] = is_upper(k);
         }
 
-        Self {
+        if connections.len() <= end {
+            return Err(DayError::new("no cave connections"));
+        }
+
+        Ok(Self {
             connections,
             start,
             end,
             large,
             indices
-        }
+        })
     }
 
     fn inner_routes(&self, at: usize, seen: &mut Vec<u8>, limit: u8, route: &str) -> (u64, u64) {
@@ -82,7 +95,7 @@ impl Caves {
             if *explore == self.start {
                // Never go back to the start.
             } else if *explore == self.end {
-                //println!("{},end", route);
+                trace!(Level::Trace, INFO.day, "{},end", route);
                 routes += 1;
             } else if self.large[*explore] {
                 // We can freely explore through 'large' caves.
@@ -104,10 +117,9 @@ impl Caves {
         (routes, visits)
     }
 
-    fn routes(&self, limit: u8) -> u64 {
-        let (routes, _) = self.inner_routes(0, &mut vec![0; self.indices.len()], limit, "0");
-        //println!("Visited : {} nodes.", visits);
-        routes
+    // The number of routes, and how many caves were visited finding them.
+    fn routes(&self, limit: u8) -> (u64, u64) {
+        self.inner_routes(0, &mut vec![0; self.indices.len()], limit, "0")
     }
 }
 
@@ -122,9 +134,9 @@ mod tests {
     #[test]
     fn test_data() {
         let data = include_str!("data/test_day12.dat");
-        let caves = Caves::from(data);
-        let routes1 = caves.routes(1);
-        let routes2 = caves.routes(2);
+        let caves = Caves::from(data).unwrap();
+        let (routes1, _) = caves.routes(1);
+        let (routes2, _) = caves.routes(2);
         assert_eq!(routes1, 10);
         assert_eq!(routes2, 36);
     }
@@ -132,9 +144,9 @@ mod tests {
     #[test]
     fn test_data_b() {
         let data = include_str!("data/test_day12b.dat");
-        let caves = Caves::from(data);
-        let routes1 = caves.routes(1);
-        let routes2 = caves.routes(2);
+        let caves = Caves::from(data).unwrap();
+        let (routes1, _) = caves.routes(1);
+        let (routes2, _) = caves.routes(2);
         assert_eq!(routes1, 19);
         assert_eq!(routes2, 103);
     }
@@ -142,11 +154,16 @@ mod tests {
     #[test]
     fn test_data_c() {
         let data = include_str!("data/test_day12c.dat");
-        let caves = Caves::from(data);
-        let routes1 = caves.routes(1);
-        let routes2 = caves.routes(2);
+        let caves = Caves::from(data).unwrap();
+        let (routes1, _) = caves.routes(1);
+        let (routes2, _) = caves.routes(2);
         assert_eq!(routes1, 226);
         assert_eq!(routes2, 3509);
     }
-}
 
+    #[test]
+    fn test_bad_data() {
+        let err = Caves::from("start-A\nA-\nA-end").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(2), Some(1)));
+    }
+}
@@ -1,28 +1,45 @@
-use crate::{DaySolver, DayResult};
+use crate::{sections, Answer, DayError, DayInfo, Frame, PartResult, Solution, Visualize};
 use std::collections::HashMap;
-use std::time::SystemTime;
 
 pub struct Day {}
 
-impl DaySolver for Day {
-    fn solve(&self) -> DayResult {
-        let data = include_str!("data/day13.dat");
-        let start = SystemTime::now();
-        let transparency = Transparency::new(data);
+pub const INFO: DayInfo = DayInfo { day: 13, title: "Transparent Origami" };
+
+impl Solution for Day {
+    type Parsed = Transparency;
+
+    fn bundled_input(&self) -> &'static str {
+        include_str!("data/day13.dat")
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        Transparency::new(input)
+    }
+
+    fn part1(&self, transparency: &Self::Parsed) -> Result<PartResult, DayError> {
         let single_folded = transparency.fold(1);
+        Ok(PartResult::new(single_folded.len(),
+                           format!("After one fold, there are {} points.", single_folded.len())))
+    }
+
+    fn part2(&self, transparency: &Self::Parsed) -> Result<PartResult, DayError> {
         let plot = plot(transparency.fold(0));
-        let timed = SystemTime::now().duration_since(start).unwrap();
-        let description = format!(
-            "After one fold, there are {} points. After complete folding: \n{}",
-            single_folded.len(),
-            plot);
-
-        DayResult {
-            description,
-            part1: format!("{}", single_folded.len()),
-            part2: plot,
-            timing_us: timed.as_micros(),
-        }
+        let description = format!("After complete folding: \n{}", plot.join("\n"));
+        Ok(PartResult::new(Answer::Grid(plot), description))
+    }
+
+    fn visualizer(&self) -> Option<&dyn Visualize<Parsed = Self::Parsed>> {
+        Some(self)
+    }
+}
+
+// The paper after each fold.
+impl Visualize for Day {
+    fn frames(&self, transparency: &Transparency) -> Result<Vec<Frame>, DayError> {
+        let folds = transparency.folds.len();
+        Ok((1..=folds)
+            .map(|n| Frame::new(format!("After {} of {} folds", n, folds), plot(transparency.fold(n)).join("\n")))
+            .collect())
     }
 }
 
@@ -36,12 +53,12 @@ enum Fold {
 }
 
 #[derive(Debug)]
-struct Transparency {
+pub struct Transparency {
     points: Vec<Point>,
     folds: Vec<Fold>,
 }
 
-fn plot(points: HashMap<Point, bool>) -> String {
+fn plot(points: HashMap<Point, bool>) -> Vec<String> {
     // Find boundaries of the system.
     let mut max_x = 0;
     let mut max_y = 0;
@@ -64,7 +81,7 @@ fn plot(points: HashMap<Point, bool>) -> String {
         output.push(line.join(""));
     }
 
-    output.join("\n")
+    output
 }
 
 impl Transparency {
@@ -79,7 +96,7 @@ impl Transparency {
         };
 
         for p in &self.points {
-            // Apply folds as necessary
+            // Apply folds as necessary. `new` has checked none of them folds a dot off the sheet.
             let mut x = p.0;
             let mut y = p.1;
             for fold_index in 0..folds {
@@ -101,27 +118,45 @@ impl Transparency {
         point_map
     }
 
-    fn new(data: &str) -> Self {
+    // The points, then a blank line, then the folds.
+    fn new(data: &str) -> Result<Self, DayError> {
+        let sections = sections(data);
         let mut points = vec![];
         let mut folds = vec![];
-        for line in data.lines() {
-            if line.starts_with("fold along y=") {
-                let val = line.split('=').collect::<Vec<&str>>()[1].parse::<u64>().unwrap();
-                folds.push(Fold::FoldY(val))
-            } else if line.starts_with("fold along x=") {
-                let val = line.split('=').collect::<Vec<&str>>()[1].parse::<u64>().unwrap();
-                folds.push(Fold::FoldX(val))
-            } else if line.contains(',') {
-                let vals = line.split(',').collect::<Vec<&str>>();
-                points.push(Point(vals[0].parse::<u64>().unwrap(),
-                                  vals[1].parse::<u64>().unwrap()));
+        if let Some(dots) = sections.first() {
+            for line in dots.lines() {
+                let (x, y) = line.split_once(line.text, ",", "x,y")?;
+                points.push(Point(line.parse(x)?, line.parse(y)?));
+            }
+        }
+        if sections.len() != 2 {
+            return Err(DayError::new("expected points, a blank line, then folds"));
+        }
+        // Where the dots are after each fold so far, to check that none fold off the sheet.
+        let mut folded = points.iter().map(|p| [p.0, p.1]).collect::<Vec<[u64; 2]>>();
+        for line in sections[1].lines() {
+            let fold = line.strip_prefix(line.text, "fold along ", "fold along x=N")?;
+            let (axis, at) = line.split_once(fold, "=", "x=N")?;
+            let (fold, along) = match axis {
+                "x" => (Fold::FoldX(line.parse(at)?), 0),
+                "y" => (Fold::FoldY(line.parse(at)?), 1),
+                _ => return Err(line.error(axis, format!("expected a fold along x or y, found '{}'", axis))),
+            };
+            let f = match fold {
+                Fold::FoldX(f) | Fold::FoldY(f) => f,
+            };
+            for dot in folded.iter_mut().filter(|dot| dot[along] > f) {
+                dot[along] = f.checked_mul(2).and_then(|edge| edge.checked_sub(dot[along])).ok_or_else(|| {
+                    line.error(at, format!("a dot at {}={} would fold past the edge", axis, dot[along]))
+                })?;
             }
+            folds.push(fold);
         }
 
-        Self {
+        Ok(Self {
             points,
             folds,
-        }
+        })
     }
 }
 
@@ -132,11 +167,25 @@ mod tests {
     #[test]
     fn test_data() {
         let data = include_str!("data/test_day13.dat");
-        let transparency = Transparency::new(data);
+        let transparency = Transparency::new(data).unwrap();
         let single_folded = transparency.fold(1);
         let all_folded = transparency.fold(0);
         assert_eq!(single_folded.len(), 17);
         assert_eq!(all_folded.len(), 16);
     }
+
+    #[test]
+    fn test_bad_data() {
+        let err = Transparency::new("6,10\n\nfold along y=7\nfold along x=-5").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(4), Some(14)));
+        let err = Transparency::new("6,10\nfold along z=7").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(2), Some(1)));
+        let err = Transparency::new("1,1\n\nfold along y=0").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(3), Some(14)));
+        assert_eq!(err.reason, "a dot at y=1 would fold past the edge");
+        // Only where the dots have got to by each fold matters.
+        assert!(Transparency::new("9,0\n\nfold along x=5\nfold along x=1").is_ok());
+        assert!(Transparency::new("9,0\n\nfold along x=5\nfold along x=0").is_err());
+    }
 }
 
@@ -1,26 +1,32 @@
-use crate::{DaySolver, DayResult};
-use std::time::SystemTime;
+use crate::{sections, DayError, DayInfo, Line, PartResult, Solution};
 use std::collections::HashMap;
 
 pub struct Day {}
 
-impl DaySolver for Day {
-    fn solve(&self) -> DayResult {
-        let data = include_str!("data/day14.dat");
-        let start = SystemTime::now();
-        let mut system = PolymerSystem::new(data);
+pub const INFO: DayInfo = DayInfo { day: 14, title: "Extended Polymerization" };
+
+impl Solution for Day {
+    type Parsed = PolymerSystem;
+
+    fn bundled_input(&self) -> &'static str {
+        include_str!("data/day14.dat")
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        PolymerSystem::new(input)
+    }
+
+    // Each part evolves its own copy, so has to build up its own cache.
+    fn part1(&self, system: &Self::Parsed) -> Result<PartResult, DayError> {
+        let mut system = system.clone();
         let strength10 = system.evolve_caching(10);
+        Ok(system.with_metrics(PartResult::new(strength10, format!("Strength after 10 steps: {}", strength10))))
+    }
+
+    fn part2(&self, system: &Self::Parsed) -> Result<PartResult, DayError> {
+        let mut system = system.clone();
         let strength40 = system.evolve_caching(40);
-        let timed = SystemTime::now().duration_since(start).unwrap();
-        let description = format!("Strength after 10 steps: {}, strength after 40: {}",
-            strength10, strength40);
-
-        DayResult {
-            description,
-            part1: format!("{}", strength10),
-            part2: format!("{}", strength40),
-            timing_us: timed.as_micros(),
-        }
+        Ok(system.with_metrics(PartResult::new(strength40, format!("Strength after 40 steps: {}", strength40))))
     }
 }
 
@@ -32,11 +38,12 @@ struct CharPairTo(CharPair, usize);
 
 type CharFrequency = [u64;26];
 
-#[derive(Debug)]
-struct PolymerSystem {
+#[derive(Clone, Debug)]
+pub struct PolymerSystem {
     start: Vec<u32>,
     rules: HashMap<CharPair, u32>,
     cache: HashMap<CharPairTo, CharFrequency>,
+    cache_hits: u64,
 }
 
 impl PolymerSystem {
@@ -64,7 +71,7 @@ impl PolymerSystem {
         // Now find min, max and strength values.
         // Find most and least common.
         let mut most = 1;
-        let mut least = u64::max_value();
+        let mut least = u64::MAX;
         for c in freq.iter() {
             most = std::cmp::max(most, *c);
             if *c > 0 {
@@ -78,6 +85,7 @@ impl PolymerSystem {
     fn evolve_caching_pair(&mut self, from: &CharPairTo) -> CharFrequency {
         // We've finished. We just get the final char.
         if let Some(freq) = self.cache.get(from) {
+            self.cache_hits += 1;
             *freq
         } else if from.1 == 0 {
             let mut freq = [0u64;26];
@@ -105,29 +113,55 @@ impl PolymerSystem {
         }
     }
 
-    fn new(data: &str) -> Self {
-        let mut start = vec![];
+    fn with_metrics(&self, result: PartResult) -> PartResult {
+        result
+            .with_metric("cache_hits", self.cache_hits)
+            .with_metric("cache_entries", self.cache.len() as u64)
+    }
+
+    // The template, then a blank line, then the rules as 'AB -> C'.
+    fn new(data: &str) -> Result<Self, DayError> {
+        let sections = sections(data);
+        let mut template = sections.first().into_iter().flat_map(|s| s.lines());
+        let start = match template.next() {
+            Some(line) => elements(&line, line.text)?,
+            None => return Err(DayError::at(1, 1, "expected a polymer template")),
+        };
+        if let Some(line) = template.next() {
+            return Err(line.error(line.text, "expected a blank line after the template"));
+        }
+
         let mut rules = HashMap::new();
-        for (i, line) in data.lines().enumerate() {
-            if i == 0 {
-                start = line.chars().map(|c| c.to_digit(36).unwrap() - 10).collect::<Vec<u32>>();
-            } else if line.contains("->") {
-                let chars = line.chars().collect::<Vec<char>>();
-                let c1 = chars[0].to_digit(36).unwrap() - 10;
-                let c2 = chars[1].to_digit(36).unwrap() - 10;
-                let insertion = chars[6].to_digit(36).unwrap() - 10;
-                rules.insert(CharPair(c1, c2), insertion);
-            }
+        for line in sections.iter().skip(1).flat_map(|s| s.lines()) {
+            let (pair, insertion) = line.split_once(line.text, " -> ", "AB -> C")?;
+            match (elements(&line, pair)?.as_slice(), elements(&line, insertion)?.as_slice()) {
+                (&[c1, c2], &[insertion]) => rules.insert(CharPair(c1, c2), insertion),
+                _ => return Err(line.expected(line.text, "AB -> C")),
+            };
         }
 
-        PolymerSystem {
+        Ok(PolymerSystem {
             start,
             rules,
-            cache: Default::default()
-        }
+            cache: Default::default(),
+            cache_hits: 0,
+        })
     }
 }
 
+// Elements are the letters A-Z, numbered from zero.
+fn elements<'a>(line: &Line<'a>, token: &'a str) -> Result<Vec<u32>, DayError> {
+    line.chars(token)
+        .map(|(column, c)| {
+            if c.is_ascii_uppercase() {
+                Ok(c as u32 - 'A' as u32)
+            } else {
+                Err(DayError::at(line.no, column, format!("expected an element A-Z, found '{}'", c)))
+            }
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -135,11 +169,19 @@ mod tests {
     #[test]
     fn test_data() {
         let data = include_str!("data/test_day14.dat");
-        let mut system = PolymerSystem::new(data);
+        let mut system = PolymerSystem::new(data).unwrap();
         let strength10 = system.evolve_caching(10);
         let strength40 = system.evolve_caching(40);
         assert_eq!(strength10, 1588);
         assert_eq!(strength40, 2188189693529)
     }
+
+    #[test]
+    fn test_bad_data() {
+        let err = PolymerSystem::new("NNCB\n\nCH -> B\nHH -> n").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(4), Some(7)));
+        let err = PolymerSystem::new("NNCB\n\nCH->B").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(3), Some(1)));
+    }
 }
 
@@ -1,38 +1,53 @@
 use std::cmp::Ordering;
-use crate::{DaySolver, DayResult};
-use std::time::SystemTime;
+use crate::{trace, DayError, DayInfo, Grid, Level, PartResult, Solution};
 use std::collections::BinaryHeap;
 use std::cmp::Reverse;
 
 pub struct Day {}
 
-impl DaySolver for Day {
-    fn solve(&self) -> DayResult {
-        let data = include_str!("data/day15.dat");
-        let start = SystemTime::now();
+pub const INFO: DayInfo = DayInfo { day: 15, title: "Chiton" };
 
-        let cave_map1 = CaveMap::from(data, 1);
-        let path_risk1 = cave_map1.find_path();
+impl Solution for Day {
+    // The risk of each cell, less one.
+    type Parsed = Grid<u8>;
 
-        let cave_map5 = CaveMap::from(data, 5);
-        let path_risk5 = cave_map5.find_path();
+    fn bundled_input(&self) -> &'static str {
+        include_str!("data/day15.dat")
+    }
 
-        let timed = SystemTime::now().duration_since(start).unwrap();
-        let description = format!("Least risk path has risk {}, or on the bigger map {}", path_risk1, path_risk5);
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        risks(input)
+    }
 
-        DayResult {
-            description,
-            part1: format!("{}", path_risk1),
-            part2: format!("{}", path_risk5),
-            timing_us: timed.as_micros(),
-        }
+    fn part1(&self, risks: &Self::Parsed) -> Result<PartResult, DayError> {
+        let path_risk1 = CaveMap::expand(risks, 1).find_path();
+        Ok(PartResult::new(path_risk1, format!("Least risk path has risk {}", path_risk1)))
+    }
+
+    fn part2(&self, risks: &Self::Parsed) -> Result<PartResult, DayError> {
+        let path_risk5 = CaveMap::expand(risks, 5).find_path();
+        Ok(PartResult::new(path_risk5, format!("On the bigger map, least risk path has risk {}", path_risk5)))
+    }
+}
+
+// Read the square map of risks 1-9, storing each less one.
+fn risks(data: &str) -> Result<Grid<u8>, DayError> {
+    let risks_minus = Grid::parse(data, |c| match c.to_digit(10) {
+        Some(risk) if risk > 0 => Ok(risk as u8 - 1),
+        _ => Err(format!("expected a risk 1-9, found '{}'", c)),
+    })?;
+    if risks_minus.width() != risks_minus.height() {
+        return Err(DayError::new("cave map must be square"));
     }
+    Ok(risks_minus)
 }
 
 #[derive(Debug)]
 struct CaveMap {
-    risks_minus: Vec<Vec<u8>>,
-    risks_full: Vec<Vec<u8>>,
+    #[allow(dead_code)]
+    risks_minus: Grid<u8>,
+    risks_full: Grid<u8>,
+    #[allow(dead_code)]
     grid_size: usize,
     full_grid_size: usize,
 }
@@ -65,29 +80,19 @@ impl PartialEq for TryMove {
 }
 
 impl CaveMap {
-    fn from(data: &str, multiplier: usize) -> Self {
-        let mut risks_minus = vec![];
-        for line in data.lines() {
-            let risk_line = line.chars()
-                .map(|c| String::from(c).parse::<u8>().unwrap() - 1)
-                .collect::<Vec<u8>>();
-            risks_minus.push(risk_line);
-        }
-        let grid_size = risks_minus.len();
+    fn expand(risks_minus: &Grid<u8>, multiplier: usize) -> Self {
+        let grid_size = risks_minus.width();
         let full_grid_size = multiplier * grid_size;
 
         // Expand to write the full grid, to avoid recalculating.
-        let mut risks_full = vec![vec![0; full_grid_size]; full_grid_size];
-        for i in 0..full_grid_size {
-            for j in 0..full_grid_size {
-                let incr = (i / grid_size) + (j / grid_size);
-                let base_val = risks_minus[i % grid_size][j % grid_size];
-                risks_full[i][j] = ((base_val as usize + incr) % 9) as u8 + 1;
-            }
-        }
+        let risks_full = Grid::from_fn(full_grid_size, full_grid_size, |(i, j)| {
+            let incr = (i / grid_size) + (j / grid_size);
+            let base_val = risks_minus[(i % grid_size, j % grid_size)];
+            ((base_val as usize + incr) % 9) as u8 + 1
+        });
 
         Self {
-            risks_minus,
+            risks_minus: risks_minus.clone(),
             risks_full,
             grid_size,
             full_grid_size,
@@ -95,7 +100,7 @@ impl CaveMap {
     }
 
     fn find_path(&self) -> u64 {
-        let mut costs_to = vec![vec![u64::max_value(); self.full_grid_size]; self.full_grid_size];
+        let mut costs_to = Grid::new(self.full_grid_size, self.full_grid_size, u64::MAX);
         let mut worklist: BinaryHeap<Reverse<TryMove>> = BinaryHeap::new();
 
         worklist.push(Reverse(TryMove{
@@ -105,34 +110,36 @@ impl CaveMap {
             best_attainable: 2 * self.full_grid_size as u64,
         }));
 
-        costs_to[0][0] = 0;
+        costs_to[(0, 0)] = 0;
         while !worklist.is_empty() {
             self.paths_update(&mut worklist, &mut costs_to);
         }
 
         // Print out the final grid
-        /*
-        for l in &costs_to {
-            let strs = l.iter().map(|r| if *r > 10000 { String::from("|****|") } else { format!("|{:04}|", r)}).collect::<Vec<String>>();
-            println!("{}", strs.join("."));
-        }
-        println!("{}x{}", self.full_grid_size, self.full_grid_size);
-        */
-        costs_to[self.full_grid_size - 1][self.full_grid_size - 1]
+        trace!(Level::Trace, INFO.day, "{}x{}\n{}", self.full_grid_size, self.full_grid_size,
+            costs_to.rows().map(|l| {
+                let strs = l.iter().map(|r| if *r > 10000 { String::from("|****|") } else { format!("|{:04}|", r)}).collect::<Vec<String>>();
+                strs.join(".")
+            }).collect::<Vec<String>>().join("\n"));
+        costs_to[self.end()]
+    }
+
+    fn end(&self) -> (usize, usize) {
+        (self.full_grid_size - 1, self.full_grid_size - 1)
     }
 
     // Check whether we can improve.
-    fn could_improve(&self, try_move: &TryMove, costs: &[Vec<u64>]) -> bool {
-        if costs[try_move.i][try_move.j] <= try_move.cost {
+    fn could_improve(&self, try_move: &TryMove, costs: &Grid<u64>) -> bool {
+        if costs[(try_move.i, try_move.j)] <= try_move.cost {
             false
         } else {
-            let current_best = costs[self.full_grid_size - 1][self.full_grid_size - 1];
+            let current_best = costs[self.end()];
             self.best_case(try_move) < current_best
         }
     }
 
-    fn could_beat(&self, try_move: &TryMove, costs: &[Vec<u64>]) -> bool {
-        let current_best = costs[self.full_grid_size - 1][self.full_grid_size - 1];
+    fn could_beat(&self, try_move: &TryMove, costs: &Grid<u64>) -> bool {
+        let current_best = costs[self.end()];
         self.best_case(try_move) < current_best
     }
 
@@ -142,61 +149,41 @@ impl CaveMap {
     }
 
     fn risk_at(&self, i: usize, j: usize) -> u64 {
-        self.risks_full[i][j] as u64
+        self.risks_full[(i, j)] as u64
             /*
         // The grid repeats, but for each repeat increments, wrapping to 1-8.
         let incr = (i / self.grid_size) + (j / self.grid_size);
-        let base_val = self.risks_minus[i % self.grid_size][j % self.grid_size];
+        let base_val = self.risks_minus[(i % self.grid_size, j % self.grid_size)];
         ((base_val as usize + incr) % 9) as u64 + 1
 
              */
     }
 
-    fn paths_update(&self, worklist: &mut BinaryHeap<Reverse<TryMove>>, costs: &mut Vec<Vec<u64>>) {
+    fn paths_update(&self, worklist: &mut BinaryHeap<Reverse<TryMove>>, costs: &mut Grid<u64>) {
         let try_move = worklist.pop().unwrap();
         let TryMove { i, j, cost, .. } = try_move.0;
         if !self.could_beat(&try_move.0, costs) {
             return
         }
 
-        for di in -1..=1 {
-            for dj in -1..=1 {
-                // We ignore not moving at all, and diagonal moves.
-                if di == 0 && dj == 0 || (di * dj != 0) {
-                    continue;
-                }
-
-                // We ignore falling off the low end.
-                if (di < 0 && i == 0) || (dj < 0 && j == 0) {
-                    continue;
-                }
-
-                let new_i = (i as i64 + di) as usize;
-                let new_j = (j as i64 + dj) as usize;
-
-                // We ignore falling off the high end.
-                if (new_i >= self.full_grid_size) ||
-                    (new_j >= self.full_grid_size) {
-                    continue;
-                }
-
-                let new_cost = cost + self.risk_at(new_i, new_j);
-                let best_attainable =
-                    new_cost + (self.full_grid_size - new_i) as u64
-                        + (self.full_grid_size - new_j) as u64;
-
-                let new_move = TryMove {
-                    i: new_i,
-                    j: new_j,
-                    cost: new_cost,
-                    best_attainable,
-                };
-
-                // Check whether this does lead to an improvement.
-                if self.could_improve(&new_move, costs) {
-                    costs[new_i][new_j] = new_cost;
-                    worklist.push(Reverse(new_move))
-                }
+        // Diagonal moves aren't allowed, and the grid takes care of the edges.
+        for (new_i, new_j) in self.risks_full.neighbours4((i, j)) {
+            let new_cost = cost + self.risk_at(new_i, new_j);
+            let best_attainable =
+                new_cost + (self.full_grid_size - new_i) as u64
+                    + (self.full_grid_size - new_j) as u64;
+
+            let new_move = TryMove {
+                i: new_i,
+                j: new_j,
+                cost: new_cost,
+                best_attainable,
+            };
+
+            // Check whether this does lead to an improvement.
+            if self.could_improve(&new_move, costs) {
+                costs[(new_i, new_j)] = new_cost;
+                worklist.push(Reverse(new_move))
             }
         }
     }
@@ -210,13 +197,22 @@ mod tests {
     fn test_data() {
         let data = include_str!("data/test_day15.dat");
 
-        let cave_map1 = CaveMap::from(data, 1);
+        let risks = risks(data).unwrap();
+        let cave_map1 = CaveMap::expand(&risks, 1);
         let path_risk1 = cave_map1.find_path();
         assert_eq!(path_risk1, 40);
 
-        let cave_map5 = CaveMap::from(data, 5);
+        let cave_map5 = CaveMap::expand(&risks, 5);
         let path_risk5 = cave_map5.find_path();
         assert_eq!(path_risk5, 315);
     }
+
+    #[test]
+    fn test_bad_data() {
+        let err = risks("116\n108\n213").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(2), Some(2)));
+        let err = risks("116\n13\n213").unwrap_err();
+        assert_eq!(err.line, Some(2));
+    }
 }
 
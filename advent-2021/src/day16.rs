@@ -1,141 +1,104 @@
-use crate::{DayResult, DaySolver};
-use std::time::SystemTime;
+use crate::{Answer, DayError, DayInfo, PartResult, Solution};
+use std::convert::TryFrom;
 
 pub struct Day {}
 
-impl DaySolver for Day {
-    fn solve(&self) -> DayResult {
-        let data = include_str!("data/day16.dat");
-        let start = SystemTime::now();
-
-        let mut program = Program::new(data);
-        let packets = program.read_all_packets();
-        let version_total = program.version_total;
-        let evaluated = packets[0].evaluate();
-
-        let timed = SystemTime::now().duration_since(start).unwrap();
-        let description = format!(
-            "Sum of all versions numbers = {}, evaluated to {}",
-            version_total, evaluated
-        );
-
-        DayResult {
-            description,
-            part1: format!("{}", version_total),
-            part2: format!("{}", evaluated),
-            timing_us: timed.as_micros(),
+pub const INFO: DayInfo = DayInfo { day: 16, title: "Packet Decoder" };
+
+impl Solution for Day {
+    // The outermost packet, and the sum of the version numbers of every packet read.
+    type Parsed = (Packet, u128);
+
+    fn bundled_input(&self) -> &'static str {
+        include_str!("data/day16.dat")
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        let mut program = Program::new(input);
+        let mut packets = program.read_all_packets()?;
+        if packets.is_empty() {
+            return Err(DayError::new("transmission has no packets"));
         }
+        Ok((packets.swap_remove(0), program.version_total))
+    }
+
+    fn part1(&self, (_, version_total): &Self::Parsed) -> Result<PartResult, DayError> {
+        Ok(PartResult::new(Answer::try_from(*version_total)?, format!("Sum of all versions numbers = {}", version_total)))
+    }
+
+    fn part2(&self, (packet, _): &Self::Parsed) -> Result<PartResult, DayError> {
+        let evaluated = packet.evaluate()?;
+        Ok(PartResult::new(evaluated, format!("Packets evaluated to {}", evaluated)))
     }
 }
 
 #[derive(Debug)]
 enum PacketContents {
     Literal(u128),
-    SubPackets(Vec<Packet>),
+    Operator(Operation, Vec<Packet>),
+}
+
+// What an operator packet does with the values of its sub packets.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Operation {
+    Sum,
+    Product,
+    Minimum,
+    Maximum,
+    GreaterThan,
+    LessThan,
+    EqualTo,
+}
+
+impl Operation {
+    // The operation for a type id, or None for the literal type id 4, or anything that doesn't
+    // fit in the 3 bits a type id is read from.
+    fn from_type_id(type_id: u8) -> Option<Self> {
+        match type_id {
+            0 => Some(Self::Sum),
+            1 => Some(Self::Product),
+            2 => Some(Self::Minimum),
+            3 => Some(Self::Maximum),
+            5 => Some(Self::GreaterThan),
+            6 => Some(Self::LessThan),
+            7 => Some(Self::EqualTo),
+            _ => None,
+        }
+    }
+
+    // Comparisons only make sense between exactly two packets.
+    fn is_comparison(self) -> bool {
+        matches!(self, Self::GreaterThan | Self::LessThan | Self::EqualTo)
+    }
 }
 
 #[derive(Debug)]
-struct Packet {
+pub struct Packet {
+    #[allow(dead_code)]
     version: u8,
-    type_id: u8,
     contents: PacketContents,
 }
 
 impl Packet {
-    fn evaluate(&self) -> u64 {
-        match self.type_id {
-            0 => {
-                // Sum packet.
-                let mut acc = 0;
-                if let PacketContents::SubPackets(packets) = &self.contents {
-                    for p in packets {
-                        acc += p.evaluate()
-                    }
-                    acc
-                } else {
-                    panic!("Expected sub packets.")
-                }
-            }
-            1 => {
-                // Product packet.
-                let mut acc = 1;
-                if let PacketContents::SubPackets(packets) = &self.contents {
-                    for p in packets {
-                        acc *= p.evaluate()
-                    }
-                    acc
-                } else {
-                    panic!("Expected sub packets.")
-                }
-            }
-            2 => {
-                // Minimum packet.
-                let mut acc = u64::max_value();
-                if let PacketContents::SubPackets(packets) = &self.contents {
-                    for p in packets {
-                        acc = std::cmp::min(acc, p.evaluate())
-                    }
-                    acc
-                } else {
-                    panic!("Expected sub packets.")
-                }
-            }
-            3 => {
-                // Maximum packet.
-                let mut acc = 0u64;
-                if let PacketContents::SubPackets(packets) = &self.contents {
-                    for p in packets {
-                        acc = std::cmp::max(acc, p.evaluate())
-                    }
-                    acc
-                } else {
-                    panic!("Expected sub packets.")
-                }
-            }
-            4 => {
-                if let PacketContents::Literal(literal) = &self.contents {
-                    *literal as u64
-                } else {
-                    panic!("Expected literal")
-                }
+    fn evaluate(&self) -> Result<u64, DayError> {
+        let (operation, packets) = match &self.contents {
+            PacketContents::Literal(literal) => {
+                return u64::try_from(*literal)
+                    .map_err(|_| DayError::new(format!("literal {} is too large to evaluate", literal)))
             }
-            5 => {
-                // Greater-than packet.
-                if let PacketContents::SubPackets(packets) = &self.contents {
-                    if packets[0].evaluate() > packets[1].evaluate() {
-                        1
-                    } else {
-                        0
-                    }
-                } else {
-                    panic!("Expected sub packets")
-                }
-            }
-            6 => {
-                // Less-than packet.
-                if let PacketContents::SubPackets(packets) = &self.contents {
-                    if packets[0].evaluate() < packets[1].evaluate() {
-                        1
-                    } else {
-                        0
-                    }
-                } else {
-                    panic!("Expected sub packets")
-                }
-            }
-            7 => {
-                // Equality packet.
-                if let PacketContents::SubPackets(packets) = &self.contents {
-                    if packets[0].evaluate() == packets[1].evaluate() {
-                        1
-                    } else {
-                        0
-                    }
-                } else {
-                    panic!("Expected sub packets")
-                }
-            }
-            _ => panic!("unrecognized operation"),
+            PacketContents::Operator(operation, packets) => (*operation, packets),
+        };
+        let values = packets.iter().map(Packet::evaluate).collect::<Result<Vec<u64>, DayError>>()?;
+        let overflow = || DayError::new(format!("{:?} packet overflows", operation));
+        let empty = || DayError::new(format!("{:?} packet has no sub packets", operation));
+        match operation {
+            Operation::Sum => values.iter().try_fold(0u64, |acc, v| acc.checked_add(*v)).ok_or_else(overflow),
+            Operation::Product => values.iter().try_fold(1u64, |acc, v| acc.checked_mul(*v)).ok_or_else(overflow),
+            Operation::Minimum => values.into_iter().min().ok_or_else(empty),
+            Operation::Maximum => values.into_iter().max().ok_or_else(empty),
+            Operation::GreaterThan => Ok((values[0] > values[1]) as u64),
+            Operation::LessThan => Ok((values[0] < values[1]) as u64),
+            Operation::EqualTo => Ok((values[0] == values[1]) as u64),
         }
     }
 }
@@ -149,7 +112,7 @@ struct Program {
 
 impl Program {
     fn new(string_data: &str) -> Self {
-        let mut data = string_data.chars().collect::<Vec<char>>();
+        let mut data = string_data.trim().chars().collect::<Vec<char>>();
         data.reverse();
 
         Self {
@@ -164,119 +127,127 @@ impl Program {
         self.buffer.iter().all(|c| *c == '0') && self.data.iter().all(|c| *c == '0')
     }
 
-    fn read_all_packets(&mut self) -> Vec<Packet> {
+    fn read_all_packets(&mut self) -> Result<Vec<Packet>, DayError> {
         let mut all_packets = vec![];
         while !self.ended() {
-            all_packets.push(self.next_packet());
+            all_packets.push(self.next_packet()?);
         }
-        all_packets
+        Ok(all_packets)
     }
 
-    fn next_packet(&mut self) -> Packet {
+    fn next_packet(&mut self) -> Result<Packet, DayError> {
         // Take 3 for the version.
-        let version = self.read_3_as_u8();
+        let version = self.read_3_as_u8()?;
         self.version_total += version as u128;
         // Take 3 for the ID.
-        let type_id = self.read_3_as_u8();
+        let type_id = self.read_3_as_u8()?;
         // Decide on the type.
-        let contents = if type_id == 4 {
+        let contents = match Operation::from_type_id(type_id) {
             // Reading a literal.
-            PacketContents::Literal(self.read_literal())
-        } else {
+            None if type_id == 4 => PacketContents::Literal(self.read_literal()?),
+            None => return Err(self.error(format!("unknown packet type {}", type_id))),
             // Nested
-            self.read_sub_packets()
+            Some(operation) => {
+                let packets = self.read_sub_packets()?;
+                if operation.is_comparison() && packets.len() != 2 {
+                    return Err(self.error(format!("comparison packet has {} sub packets", packets.len())));
+                }
+                PacketContents::Operator(operation, packets)
+            }
         };
 
-        Packet {
-            version,
-            type_id,
-            contents,
-        }
+        Ok(Packet { version, contents })
     }
 
-    fn read_sub_packets(&mut self) -> PacketContents {
+    fn read_sub_packets(&mut self) -> Result<Vec<Packet>, DayError> {
         // Check the length type
-        let length_type = self.read_one_u8();
+        let length_type = self.read_one_u8()?;
 
         let mut sub_packets = vec![];
         if length_type == 0 {
             // Read 15 bits, as a bit length.
-            let bit_length = self.read_n_as_u32(15);
+            let bit_length = self.read_n_as_u32(15)?;
             let end_at = self.ptr + bit_length as u128;
             while self.ptr < end_at {
-                sub_packets.push(self.next_packet());
+                sub_packets.push(self.next_packet()?);
             }
         } else {
             // read 11 bits, as a packet count.
-            let packet_count = self.read_n_as_u32(11) as usize;
+            let packet_count = self.read_n_as_u32(11)? as usize;
             for _ in 0..packet_count {
-                sub_packets.push(self.next_packet());
+                sub_packets.push(self.next_packet()?);
             }
         }
 
-        PacketContents::SubPackets(sub_packets)
+        Ok(sub_packets)
     }
 
-    fn read_literal(&mut self) -> u128 {
+    fn read_literal(&mut self) -> Result<u128, DayError> {
         let mut cont = 1u8;
         let mut val = 0u128;
         while cont == 1u8 {
-            val *= 16u128;
-            cont = self.read_one_u8();
-            val += self.read_4_as_u8() as u128;
+            val = val.checked_mul(16u128).ok_or_else(|| self.error("literal is too large"))?;
+            cont = self.read_one_u8()?;
+            val += self.read_4_as_u8()? as u128;
         }
 
-        val
+        Ok(val)
     }
 
-    fn read_n_as_u32(&mut self, n: usize) -> u32 {
+    fn read_n_as_u32(&mut self, n: usize) -> Result<u32, DayError> {
         let mut value = 0u32;
         for _ in 0..n {
             value *= 2;
-            value += self.read_one_u8() as u32
+            value += self.read_one_u8()? as u32
         }
-        value
+        Ok(value)
     }
 
-    fn read_3_as_u8(&mut self) -> u8 {
+    fn read_3_as_u8(&mut self) -> Result<u8, DayError> {
         let mut value = 0u8;
         for power in [4, 2, 1].iter() {
-            value += power * self.read_one_u8()
+            value += power * self.read_one_u8()?
         }
-        value
+        Ok(value)
     }
 
-    fn read_4_as_u8(&mut self) -> u8 {
+    fn read_4_as_u8(&mut self) -> Result<u8, DayError> {
         let mut value = 0u8;
         for power in [8, 4, 2, 1].iter() {
-            value += power * self.read_one_u8()
+            value += power * self.read_one_u8()?
         }
-        value
+        Ok(value)
     }
 
-    fn read_one_u8(&mut self) -> u8 {
-        match self.read_one() {
-            Some('1') => 1u8,
-            Some('0') => 0u8,
-            Some(_) => panic!("unexpected char in input"),
-            _ => panic!("reached end of input"),
+    fn read_one_u8(&mut self) -> Result<u8, DayError> {
+        match self.read_one()? {
+            Some('1') => Ok(1u8),
+            Some(_) => Ok(0u8),
+            None => Err(self.error("reached end of input")),
         }
     }
 
-    fn read_one(&mut self) -> Option<char> {
+    fn read_one(&mut self) -> Result<Option<char>, DayError> {
         self.ptr += 1;
         if let Some(bin_digit) = self.buffer.pop() {
-            Some(bin_digit)
+            Ok(Some(bin_digit))
         } else if let Some(hex_digit) = self.data.pop() {
             // We push the digits into the buffer (in reverse order so we 'take' from the front.
-            let hex_value = hex_digit.to_digit(16).unwrap();
+            let hex_value = hex_digit.to_digit(16)
+                .ok_or_else(|| self.error(format!("expected a hex digit, found '{}'", hex_digit)))?;
             self.buffer = format!("{:04b}", hex_value).chars().collect::<Vec<char>>();
             self.buffer.reverse();
-            self.buffer.pop()
+            Ok(self.buffer.pop())
         } else {
-            None
+            Ok(None)
         }
     }
+
+    // An error at the hex digit currently being read.
+    fn error<S: Into<String>>(&self, reason: S) -> DayError {
+        let column = (std::cmp::max(self.ptr, 1) - 1) / 4 + 1;
+        DayError::at(1, column as usize, reason)
+    }
 }
 
 #[cfg(test)]
@@ -296,7 +267,7 @@ mod tests {
         .iter()
         {
             let mut program = Program::new(example.0);
-            let _packets = program.read_all_packets();
+            let _packets = program.read_all_packets().unwrap();
             assert_eq!(program.version_total, example.1);
         }
 
@@ -313,8 +284,25 @@ mod tests {
         .iter()
         {
             let mut program = Program::new(example.0);
-            let packets = program.read_all_packets();
-            assert_eq!(packets[0].evaluate(), example.1);
+            let packets = program.read_all_packets().unwrap();
+            assert_eq!(packets[0].evaluate().unwrap(), example.1);
         }
     }
+
+    #[test]
+    fn test_bad_values() {
+        // A literal of 2^64, and the product of two literals of 2^32.
+        let literal = Program::new("F2308421084210842108400").read_all_packets().unwrap();
+        assert!(literal[0].evaluate().unwrap_err().reason.contains("too large"));
+        let product = Program::new("0600BC8C2108421007918421084200").read_all_packets().unwrap();
+        assert_eq!(product[0].evaluate().unwrap_err().reason, "Product packet overflows");
+    }
+
+    #[test]
+    fn test_bad_data() {
+        let err = Program::new("D2FE2G").read_all_packets().unwrap_err();
+        assert_eq!((err.line, err.column), (Some(1), Some(6)));
+        let err = Program::new("38006F4529").read_all_packets().unwrap_err();
+        assert_eq!(err.reason, "reached end of input");
+    }
 }
@@ -1,34 +1,49 @@
-use crate::{DayResult, DaySolver};
+use crate::{DayError, DayInfo, Line, PartResult, Solution};
 use std::collections::HashMap;
-use std::time::SystemTime;
 use std::collections::hash_map::Entry;
 
 pub struct Day {}
 
-impl DaySolver for Day {
-    fn solve(&self) -> DayResult {
-        let start = SystemTime::now();
+pub const INFO: DayInfo = DayInfo { day: 17, title: "Trick Shot" };
 
-        let (max_y, valid) = max_height(143, 177, -106, -71);
+impl Solution for Day {
+    // The target area bounds x_min, x_max, y_min, y_max.
+    type Parsed = (i64, i64, i64, i64);
 
-        let timed = SystemTime::now().duration_since(start).unwrap();
-        let description = format!(
-            "Best trickshot height is {}. In total {} possible trajectories.",
-            max_y, valid
-        );
+    fn bundled_input(&self) -> &'static str {
+        include_str!("data/day17.dat")
+    }
 
-        DayResult {
-            description,
-            part1: format!("{}", max_y),
-            part2: format!("{}", valid),
-            timing_us: timed.as_micros(),
-        }
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        target_area(input)
+    }
+
+    fn part1(&self, &(x_min, x_max, y_min, y_max): &Self::Parsed) -> Result<PartResult, DayError> {
+        let (max_y, _) = max_height(x_min, x_max, y_min, y_max);
+        Ok(PartResult::new(max_y, format!("Best trickshot height is {}.", max_y)))
+    }
+
+    fn part2(&self, &(x_min, x_max, y_min, y_max): &Self::Parsed) -> Result<PartResult, DayError> {
+        let (_, valid) = max_height(x_min, x_max, y_min, y_max);
+        Ok(PartResult::new(valid, format!("In total {} possible trajectories.", valid)))
     }
 }
 
+// Read the target area, given as 'target area: x=a..b, y=c..d'.
+fn target_area(data: &str) -> Result<(i64, i64, i64, i64), DayError> {
+    let line = Line { no: 1, text: data.trim() };
+    let ranges = line.strip_prefix(line.text, "target area: ", "target area: x=a..b, y=c..d")?;
+    let (x, y) = line.split_once(ranges, ", ", "x=a..b, y=c..d")?;
+    let (x, y) = (line.range::<i64>(x)?, line.range::<i64>(y)?);
+    if (x.key, y.key) != ("x", "y") {
+        return Err(line.expected(ranges, "x=a..b, y=c..d"));
+    }
+    Ok((x.from, x.to, y.from, y.to))
+}
+
 fn max_height(x_min: i64, x_max: i64, y_min: i64, y_max: i64) -> (i64, usize) {
     let (max_dy, valid) = max_dy(x_min, x_max, y_min, y_max);
-    (limit_for(max_dy as f64) as i64, valid)
+    (limit_for(max_dy as f64), valid)
 }
 
 fn limit_for(dy: f64) -> i64 {
@@ -49,7 +64,7 @@ fn max_dy(x_min: i64, x_max: i64, y_min: i64, y_max: i64) -> (i64, usize) {
         }
     }
 
-    (max_dy as i64, all_valid.len())
+    (max_dy, all_valid.len())
 }
 
 fn try_target_dy(x_min: i64, x_max: i64, y_min: i64, y_max: i64, dy: i64) -> Vec<(i64, i64)> {
@@ -63,7 +78,7 @@ fn try_target_dy(x_min: i64, x_max: i64, y_min: i64, y_max: i64, dy: i64) -> Vec
                 for dx in dx_min..=dx_max {
                     // Check that we're not in a case where t has reached its limit.
                     if dx >= t {
-                        if let Entry::Vacant(e) = seen.entry(dx as i64) {
+                        if let Entry::Vacant(e) = seen.entry(dx) {
                             e.insert(true);
                             acceptable.push((dx, dy));
                         }
@@ -74,7 +89,7 @@ fn try_target_dy(x_min: i64, x_max: i64, y_min: i64, y_max: i64, dy: i64) -> Vec
             // Also check whether we can add limiting dx values. (i.e. where we slow to a stop).
             let dxs = limiting_dx(x_min, x_max, t);
             for dx in dxs {
-                if let Entry::Vacant(e) = seen.entry(dx as i64) {
+                if let Entry::Vacant(e) = seen.entry(dx) {
                     e.insert( true);
                     acceptable.push((dx, dy));
                 }
@@ -144,6 +159,14 @@ fn time_for_target(target: f64, delta: f64) -> f64 {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_target_area() {
+        let target = target_area("target area: x=20..30, y=-10..-5").unwrap();
+        assert_eq!(target, (20, 30, -10, -5));
+        let err = target_area("target area: x=20..30, y=-10..-z").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(1), Some(31)));
+    }
+
     #[test]
     fn test_data() {
         let (test_max_d, test_valid) = max_height(20, 30, -10, -5);
@@ -1,28 +1,74 @@
-use crate::{DayResult, DaySolver};
-use std::time::SystemTime;
+use crate::{DayError, DayInfo, PartResult, Solution};
 
 pub struct Day {}
 
-impl DaySolver for Day {
-    fn solve(&self) -> DayResult {
-        let data = include_str!("data/day18.dat");
-        let start = SystemTime::now();
+pub const INFO: DayInfo = DayInfo { day: 18, title: "Snailfish" };
 
-        let (solved, magnitude) = solve_sums(data);
-        let max_pair = max_pair(data);
-        let description = format!(
-            "Solved the homework to :: {}, with magnitude {} . Max pair across all sums is {} .",
-            solved, magnitude, max_pair
-        );
-        let timed = SystemTime::now().duration_since(start).unwrap();
-
-        DayResult {
-            description,
-            part1: format!("{}", magnitude),
-            part2: format!("{}", max_pair),
-            timing_us: timed.as_micros(),
+impl Solution for Day {
+    // The homework, once checked to be well formed.
+    type Parsed = String;
+
+    fn bundled_input(&self) -> &'static str {
+        include_str!("data/day18.dat")
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        check_homework(input)?;
+        Ok(String::from(input))
+    }
+
+    fn part1(&self, homework: &Self::Parsed) -> Result<PartResult, DayError> {
+        let (solved, magnitude) = solve_sums(homework);
+        let description = format!("Solved the homework to :: {}, with magnitude {} .", solved, magnitude);
+        Ok(PartResult::new(magnitude, description))
+    }
+
+    fn part2(&self, homework: &Self::Parsed) -> Result<PartResult, DayError> {
+        let max_pair = max_pair(homework);
+        Ok(PartResult::new(max_pair, format!("Max pair across all sums is {} .", max_pair)))
+    }
+}
+
+// Check every line is a well formed snail number, i.e. '[a,b]' where a and b are numbers or pairs.
+fn check_homework(data: &str) -> Result<(), DayError> {
+    if data.lines().next().is_none() {
+        return Err(DayError::new("no snail numbers to add"));
+    }
+    for (i, line) in data.lines().enumerate() {
+        let chars = line.chars().collect::<Vec<char>>();
+        let end = check_pair(&chars, 0).map_err(|e| e.on_line(i + 1))?;
+        if end != chars.len() {
+            return Err(DayError::at(i + 1, end + 1, "unexpected trailing characters"));
         }
     }
+    Ok(())
+}
+
+// Check a pair starting at `at`, returning the index just past it.
+fn check_pair(chars: &[char], at: usize) -> Result<usize, DayError> {
+    let expect = |at: usize, c: char| {
+        if chars.get(at) == Some(&c) {
+            Ok(at + 1)
+        } else {
+            Err(DayError::new(format!("expected '{}'", c)).at_column(at + 1))
+        }
+    };
+    let at = expect(at, '[')?;
+    let at = check_element(chars, at)?;
+    let at = expect(at, ',')?;
+    let at = check_element(chars, at)?;
+    expect(at, ']')
+}
+
+fn check_element(chars: &[char], at: usize) -> Result<usize, DayError> {
+    match chars.get(at) {
+        Some('[') => check_pair(chars, at),
+        Some(c) if c.is_ascii_digit() => {
+            let digits = chars[at..].iter().take_while(|c| c.is_ascii_digit()).count();
+            Ok(at + digits)
+        }
+        _ => Err(DayError::new("expected a number or a pair").at_column(at + 1)),
+    }
 }
 
 fn max_pair(data: &str) -> i64 {
@@ -233,7 +279,7 @@ impl SnailMath {
 
     fn peek_back_is_number(&self) -> bool {
         if let Some(c) = self.peek_back() {
-            c.is_digit(10)
+            c.is_ascii_digit()
         } else {
             false
         }
@@ -241,7 +287,7 @@ impl SnailMath {
 
     fn peek_is_number(&self) -> bool {
         if let Some(c) = self.peek() {
-            c.is_digit(10)
+            c.is_ascii_digit()
         } else {
             false
         }
@@ -392,4 +438,14 @@ mod tests {
         assert_eq!(magnitude, 4140);
         assert_eq!(max_pair, 3993);
     }
+
+    #[test]
+    fn test_bad_data() {
+        let data = include_str!("data/test_day18.dat");
+        assert!(check_homework(data).is_ok());
+        let err = check_homework("[1,2]\n[[1,2],[3,x]]").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(2), Some(11)));
+        let err = check_homework("[1,2]]").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(1), Some(6)));
+    }
 }
@@ -1,45 +1,65 @@
-use crate::{DayResult, DaySolver};
+use crate::{checkpoint, sections, DayError, DayInfo, PartResult, Progress, Solution};
+use std::cell::OnceCell;
 use std::collections::HashMap;
-use std::time::SystemTime;
 
 pub struct Day {}
 
-impl DaySolver for Day {
-    fn solve(&self) -> DayResult {
-        let data = include_str!("data/day19.dat");
-        let start = SystemTime::now();
-
-        let mut scanners = load_scanners(data);
-        let (beacons, separation) = align_all(&mut scanners);
-
-        let timed = SystemTime::now().duration_since(start).unwrap();
-        let description = format!(
-            "After alignment, deduced {} beacons. Largest scanner separation is {} .",
-            beacons, separation
-        );
-
-        DayResult {
-            description,
-            part1: format!("{}", beacons),
-            part2: format!("{}", separation),
-            timing_us: timed.as_micros(),
+pub const INFO: DayInfo = DayInfo { day: 19, title: "Beacon Scanner" };
+
+impl Solution for Day {
+    type Parsed = Scanners;
+
+    fn bundled_input(&self) -> &'static str {
+        include_str!("data/day19.dat")
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        Ok(Scanners {
+            scanners: load_scanners(input)?,
+            aligned: OnceCell::new(),
+        })
+    }
+
+    fn part1(&self, scanners: &Self::Parsed) -> Result<PartResult, DayError> {
+        let (beacons, _) = scanners.aligned()?;
+        Ok(PartResult::new(beacons, format!("After alignment, deduced {} beacons.", beacons)))
+    }
+
+    fn part2(&self, scanners: &Self::Parsed) -> Result<PartResult, DayError> {
+        let (_, separation) = scanners.aligned()?;
+        Ok(PartResult::new(separation, format!("Largest scanner separation is {} .", separation)))
+    }
+}
+
+// The scanners as read. Aligning them gives both answers, so it's done once, by whichever part needs it first.
+pub struct Scanners {
+    scanners: Vec<Scanner>,
+    aligned: OnceCell<(usize, i64)>,
+}
+
+impl Scanners {
+    fn aligned(&self) -> Result<(usize, i64), DayError> {
+        if let Some(aligned) = self.aligned.get() {
+            return Ok(*aligned);
         }
+        let aligned = align_all(&mut self.scanners.clone())?;
+        Ok(*self.aligned.get_or_init(|| aligned))
     }
 }
 
-fn align_all(scanners: &mut Vec<Scanner>) -> (usize, i64) {
+fn align_all(scanners: &mut Vec<Scanner>) -> Result<(usize, i64), DayError> {
     let scanner_count = scanners.len();
     let mut worklist = vec![0usize];
     let mut aligned = HashMap::new();
     let mut scanner_origins: Vec<Point> = vec![[0, 0, 0]];
     aligned.insert(0, true);
 
-    while !worklist.is_empty() {
-        let working = worklist.pop().unwrap();
+    while let Some(working) = worklist.pop() {
         for i in 0..scanner_count {
             if aligned.contains_key(&i) {
                 continue;
             }
+            checkpoint(Progress::of(aligned.len(), scanner_count, "scanners aligned"))?;
 
             if let Some(transform) = align(&scanners[working], &scanners[i]) {
                 scanners[i].offset = transform.offset;
@@ -75,7 +95,7 @@ fn align_all(scanners: &mut Vec<Scanner>) -> (usize, i64) {
         }
     }
 
-    (all_points.len(), max_distance)
+    Ok((all_points.len(), max_distance))
 }
 
 fn manhattan(a: &Point, b: &Point) -> i64 {
@@ -85,7 +105,7 @@ fn manhattan(a: &Point, b: &Point) -> i64 {
 type Point = [i64; 3];
 
 // A scanner is a collection of points
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 struct Scanner {
     points: Vec<Point>,
     permutation: [usize; 3],
@@ -255,40 +275,32 @@ fn full_align_from_x(
     None
 }
 
-// Load all scanners from test input.
-fn load_scanners(data: &str) -> Vec<Scanner> {
+// Load all scanners from test input. Each report is a '--- scanner N ---' header followed
+// by its beacons as 'x,y,z', and reports are separated by blank lines.
+fn load_scanners(data: &str) -> Result<Vec<Scanner>, DayError> {
     let mut scanners = vec![];
-    let mut current_scanner = Scanner::blank();
-    for l in data.lines() {
-        if l.is_empty() {
-            continue;
+    for section in sections(data) {
+        let mut lines = section.lines();
+        let header = lines.next().expect("sections are never empty");
+        header.strip_prefix(header.text, "--- scanner ", "--- scanner N ---")
+            .and_then(|rest| header.split_once(rest, " ---", "--- scanner N ---"))
+            .and_then(|(n, _)| header.parse::<usize>(n))?;
+
+        let mut scanner = Scanner::blank();
+        for line in lines {
+            scanner.points.push(line.ints_n(line.text, ',')?);
         }
-
-        if l.split_whitespace().count() >= 3 {
-            if !current_scanner.points.is_empty() {
-                scanners.push(current_scanner);
-            }
-            current_scanner = Scanner::blank();
-        } else {
-            let p = l
-                .split(',')
-                .collect::<Vec<&str>>()
-                .iter()
-                .map(|s| s.parse::<i64>().unwrap())
-                .collect::<Vec<i64>>();
-            assert_eq!(p.len(), 3);
-            let mut point = [0; 3];
-            point[..3].clone_from_slice(&p[..3]);
-
-            current_scanner.points.push(point);
+        if scanner.points.is_empty() {
+            return Err(header.error(header.text, "expected the scanner's beacons"));
         }
+        scanners.push(scanner);
     }
 
-    if !current_scanner.points.is_empty() {
-        scanners.push(current_scanner);
+    if scanners.is_empty() {
+        return Err(DayError::new("no scanner reports"));
     }
 
-    scanners
+    Ok(scanners)
 }
 
 #[cfg(test)]
@@ -298,9 +310,17 @@ mod tests {
     #[test]
     fn test_data() {
         let data = include_str!("data/test_day19.dat");
-        let mut scanners = load_scanners(data);
-        let (beacons, separation) = align_all(&mut scanners);
+        let mut scanners = load_scanners(data).unwrap();
+        let (beacons, separation) = align_all(&mut scanners).unwrap();
         assert_eq!(beacons, 79);
         assert_eq!(separation, 3621);
     }
+
+    #[test]
+    fn test_bad_data() {
+        let err = load_scanners("--- scanner 0 ---\n404,-588,-901\n528,-643,4O9").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(3), Some(10)));
+        let err = load_scanners("--- scanner 0 ---\n404,-588").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(2), Some(1)));
+    }
 }
@@ -1,83 +1,113 @@
-use std::time::SystemTime;
-use crate::{DayResult, DaySolver};
+use crate::{parse_at, DayError, DayInfo, PartResult, Solution};
 
 pub struct Day {}
 
-impl DaySolver for Day {
-    fn solve(&self) -> DayResult {
-        let input = include_str!("data/day2.dat");
-        let start = SystemTime::now();
-        let (h1, d1) = horizontal_depth(input);
-        let (h2, d2) = aiming_horizontal_depth(input);
-        let timed = SystemTime::now().duration_since(start).unwrap();
+pub const INFO: DayInfo = DayInfo { day: 2, title: "Dive!" };
+
+impl Solution for Day {
+    type Parsed = Vec<Command>;
+
+    fn bundled_input(&self) -> &'static str {
+        include_str!("data/day2.dat")
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        commands(input)
+    }
+
+    fn part1(&self, commands: &Self::Parsed) -> Result<PartResult, DayError> {
+        let (h1, d1) = horizontal_depth(commands);
         let desc1 = format!("Reached distance {} and depth {} -> {}",
                             h1, d1,
                             h1 * d1);
+        Ok(PartResult::new(h1 * d1, desc1))
+    }
+
+    fn part2(&self, commands: &Self::Parsed) -> Result<PartResult, DayError> {
+        let (h2, d2) = aiming_horizontal_depth(commands);
         let desc2 = format!("Using corrected steering, reached distance {} and depth {} -> {}",
                             h2, d2,
                             h2 * d2);
-
-        DayResult{
-            part1: format!("{}", h1 * d1),
-            part2: format!("{}", h2 * d2),
-            description: format!("{}\n{}", desc1, desc2),
-            timing_us: timed.as_micros(),
-        }
+        Ok(PartResult::new(h2 * d2, desc2))
     }
 }
 
+// As in the other early days, the tests come before the code they cover.
 #[cfg(test)]
+#[allow(clippy::items_after_test_module)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_data() {
         let input = include_str!("./data/test_day2.dat");
-        let (h1, d1) = horizontal_depth(input);
-        let (h2, d2) = aiming_horizontal_depth(input);
+        let commands = commands(input).unwrap();
+        let (h1, d1) = horizontal_depth(&commands);
+        let (h2, d2) = aiming_horizontal_depth(&commands);
         assert_eq!(h1 * d1, 150);
         assert_eq!(h2 * d2, 900);
     }
+
+    #[test]
+    fn test_bad_data() {
+        let err = commands("forward 5\nbackward 2").err().unwrap();
+        assert_eq!((err.line, err.column), (Some(2), Some(1)));
+        let err = commands("down x").err().unwrap();
+        assert_eq!((err.line, err.column), (Some(1), Some(6)));
+    }
+}
+
+pub enum Command {
+    Forward(i32),
+    Down(i32),
+    Up(i32),
 }
 
-pub fn horizontal_depth(data :&str) -> (i32, i32) {
-    let lines = data.lines();
+pub fn horizontal_depth(commands: &[Command]) -> (i32, i32) {
     let mut depth = 0;
     let mut horizontal = 0;
-    for line in lines {
-        let split: Vec<&str> = line.split(' ').collect();
-        let val: i32 = split[1].parse().unwrap();
-        if split[0] == "forward" {
-            horizontal += val;
-        } else if split[0] == "down" {
-            depth += val;
-        } else if split[0] == "up" {
-            depth -= val;
-        } else {
-            panic!("Could not read {}", line);
+    for command in commands {
+        match command {
+            Command::Forward(val) => horizontal += val,
+            Command::Down(val) => depth += val,
+            Command::Up(val) => depth -= val,
         }
     }
     (horizontal, depth)
 }
 
-pub fn aiming_horizontal_depth(data :&str) -> (i32, i32) {
-    let lines = data.lines();
+pub fn aiming_horizontal_depth(commands: &[Command]) -> (i32, i32) {
     let mut depth = 0;
     let mut aim = 0;
     let mut horizontal = 0;
-    for line in lines {
-        let split: Vec<&str> = line.split(' ').collect();
-        let val: i32 = split[1].parse().unwrap();
-        if split[0] == "forward" {
-            horizontal += val;
-            depth += aim * val
-        } else if split[0] == "down" {
-            aim += val;
-        } else if split[0] == "up" {
-            aim -= val;
-        } else {
-            panic!("Could not read {}", line);
+    for command in commands {
+        match command {
+            Command::Forward(val) => {
+                horizontal += val;
+                depth += aim * val
+            },
+            Command::Down(val) => aim += val,
+            Command::Up(val) => aim -= val,
         }
     }
     (horizontal, depth)
-}
\ No newline at end of file
+}
+
+fn commands(data: &str) -> Result<Vec<Command>, DayError> {
+    data.lines().enumerate().map(|(i, line)| command(i + 1, line)).collect()
+}
+
+// Read a single 'forward|down|up <n>' command.
+fn command(line_no: usize, line: &str) -> Result<Command, DayError> {
+    let split: Vec<&str> = line.split(' ').collect();
+    if split.len() != 2 {
+        return Err(DayError::at(line_no, 1, format!("expected '<command> <value>', got '{}'", line)));
+    }
+    let val = parse_at(split[1], line_no, line);
+    match split[0] {
+        "forward" => Ok(Command::Forward(val?)),
+        "down" => Ok(Command::Down(val?)),
+        "up" => Ok(Command::Up(val?)),
+        _ => Err(DayError::at(line_no, 1, format!("unknown command '{}'", split[0]))),
+    }
+}
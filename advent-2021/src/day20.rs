@@ -1,43 +1,54 @@
-use crate::{DayResult, DaySolver};
-use std::time::SystemTime;
+use crate::{DayError, DayInfo, Frame, Grid, PartResult, Solution, Visualize};
 
 pub struct Day {}
 
-impl DaySolver for Day {
-    fn solve(&self) -> DayResult {
-        let data = include_str!("data/day20.dat");
-        let start = SystemTime::now();
-
-        let mut scan_data = ScannerData::new(data, 50);
-        let mut after_2 = 0;
-        let mut final_count = 0;
-        for i in 1..=50 {
-            if i == 2 {
-                after_2 = scan_data.enhance();
-            } else {
-                final_count = scan_data.enhance();
-            }
-        }
+pub const INFO: DayInfo = DayInfo { day: 20, title: "Trench Map" };
+
+impl Solution for Day {
+    // The image, buffered for up to 50 enhancements.
+    type Parsed = ScannerData;
+
+    fn bundled_input(&self) -> &'static str {
+        include_str!("data/day20.dat")
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        ScannerData::new(input, 50)
+    }
+
+    fn part1(&self, scan_data: &Self::Parsed) -> Result<PartResult, DayError> {
+        let after_2 = scan_data.clone().enhance_n(2);
+        Ok(PartResult::new(after_2, format!("After 2 enhancements, {} lit", after_2)))
+    }
 
-        let timed = SystemTime::now().duration_since(start).unwrap();
-        let description = format!(
-            "After 2 enhancements, {} lit, after 50 {}",
-            after_2, final_count
-        );
-
-        DayResult {
-            description,
-            part1: format!("{}", after_2),
-            part2: format!("{}", final_count),
-            timing_us: timed.as_micros(),
+    fn part2(&self, scan_data: &Self::Parsed) -> Result<PartResult, DayError> {
+        let final_count = scan_data.clone().enhance_n(50);
+        Ok(PartResult::new(final_count, format!("After 50 enhancements, {} lit", final_count)))
+    }
+
+    fn visualizer(&self) -> Option<&dyn Visualize<Parsed = Self::Parsed>> {
+        Some(self)
+    }
+}
+
+// The image after each enhancement.
+impl Visualize for Day {
+    fn frames(&self, scan_data: &ScannerData) -> Result<Vec<Frame>, DayError> {
+        let mut scan_data = scan_data.clone();
+        let mut frames = vec![Frame::new("The input image", scan_data.print())];
+        while scan_data.buffering > 0 {
+            let lit = scan_data.enhance();
+            frames.push(Frame::new(format!("Enhancement {}: {} lit", frames.len(), lit), scan_data.print()));
         }
+        Ok(frames)
     }
 }
 
-struct ScannerData {
+#[derive(Clone)]
+pub struct ScannerData {
     algorithm: Vec<bool>,
-    // Data can either be 0 (.), 1 (#), or 2 unset yet.
-    data: Vec<Vec<u8>>,
+    // Data can either be 0 (.), 1 (#), or 3 unset yet.
+    data: Grid<u8>,
     buffering: usize,
     inf_value: u8,
 }
@@ -57,25 +68,35 @@ fn bools_to_int(from: &[u8; 9], inf_value: u8) -> usize {
 }
 
 impl ScannerData {
-    #[allow(dead_code)]
+    // The part of the image worked out so far, which grows by a pixel on every side with
+    // each enhancement.
     fn print(&self) -> String {
-        let mut lines = vec![];
-        for l in &self.data {
-            let line = l
-                .iter()
-                .map(|c| {
-                    if *c == 0u8 {
-                        "."
-                    } else if *c == 1u8 {
-                        "#"
-                    } else {
-                        "_"
-                    }
-                })
-                .collect::<Vec<&str>>();
-            lines.push(line.join(""));
+        let edge = 1 + self.buffering;
+        self.data
+            .rows()
+            .skip(edge)
+            .take(self.data.height() - 2 * edge)
+            .map(|row| {
+                row[edge..row.len() - edge]
+                    .iter()
+                    .map(|c| match c {
+                        0 => '.',
+                        1 => '#',
+                        _ => '_',
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    // Enhance n times, returning the number lit at the end.
+    fn enhance_n(&mut self, n: usize) -> usize {
+        let mut lit = 0;
+        for _ in 0..n {
+            lit = self.enhance();
         }
-        lines.join("\n")
+        lit
     }
 
     fn enhance(&mut self) -> usize {
@@ -85,29 +106,22 @@ impl ScannerData {
         self.buffering -= 1;
 
         // Fill our scratch buffer
-        for i in (1 + self.buffering)..(self.data.len() - self.buffering - 1) {
-            for j in (1 + self.buffering)..(self.data[i].len() - self.buffering - 1) {
-                // Read the number.
-                let bools = [
-                    self.data[i - 1][j - 1],
-                    self.data[i - 1][j],
-                    self.data[i - 1][j + 1],
-                    self.data[i][j - 1],
-                    self.data[i][j],
-                    self.data[i][j + 1],
-                    self.data[i + 1][j - 1],
-                    self.data[i + 1][j],
-                    self.data[i + 1][j + 1],
-                ];
+        for i in (1 + self.buffering)..(self.data.height() - self.buffering - 1) {
+            for j in (1 + self.buffering)..(self.data.width() - self.buffering - 1) {
+                // Read the number, from the 3x3 square in reading order.
+                let mut bools = [0; 9];
+                for (n, bit) in bools.iter_mut().enumerate() {
+                    *bit = self.data[(i + n / 3 - 1, j + n % 3 - 1)];
+                }
                 let val = bools_to_int(&bools, self.inf_value);
                 if self.algorithm[val] {
                     // Note squares which are lit.
                     lit += 1;
-                    if self.data[i][j] != 1 {
+                    if self.data[(i, j)] != 1 {
                         // Note this value as needing update.
                         to_write.push((i, j));
                     }
-                } else if self.data[i][j] != 0 {
+                } else if self.data[(i, j)] != 0 {
                     // This is an on square that we need to turn off.
                     to_blank.push((i, j));
                 }
@@ -120,55 +134,57 @@ impl ScannerData {
         }
 
         for w in to_write {
-            self.data[w.0][w.1] = 1;
+            self.data[w] = 1;
         }
 
         for w in to_blank {
-            self.data[w.0][w.1] = 0;
+            self.data[w] = 0;
         }
 
         lit
     }
 
-    fn new(raw_data: &str, max_iters: usize) -> Self {
+    fn new(raw_data: &str, max_iters: usize) -> Result<Self, DayError> {
         let buffer_size = max_iters + 1;
-        let mut data_size = 0;
-        let mut data = vec![];
-        let mut algorithm = vec![];
-        let mut scanning_data = false;
-
-        for l in raw_data.lines() {
-            if l.is_empty() {
-                scanning_data = true;
-            } else if !scanning_data {
-                algorithm = l.chars().map(|c| c == '#').collect();
-            } else {
-                if data.is_empty() {
-                    // Initialize with enough space for all iterations.
-                    data_size = l.len();
-                    data = vec![vec![3; data_size + 2 * buffer_size]; buffer_size]
-                }
-                // Collect this line.
-                let mut data_line = vec![3; buffer_size];
-                let mut read_data = l
-                    .chars()
-                    .map(|c| if c == '#' { 1 } else { 0 })
-                    .collect::<Vec<u8>>();
-                data_line.append(&mut read_data);
-                data_line.append(&mut vec![3; buffer_size]);
-                data.push(data_line);
-            }
+        let lines = raw_data.lines().collect::<Vec<&str>>();
+        if lines.len() < 3 || !lines[1].is_empty() {
+            return Err(DayError::new("expected an algorithm, a blank line, then an image"));
         }
+        let algorithm_line = lines[0];
 
-        // We finally pad the data array.
-        data.append(&mut vec![vec![3; data_size + 2 * buffer_size]; buffer_size]);
+        if let Some(j) = algorithm_line.find(|c| c != '#' && c != '.') {
+            return Err(DayError::at(1, j + 1, "expected '#' or '.'"));
+        }
+        if algorithm_line.len() != 512 {
+            return Err(DayError::at(1, 1, format!("expected 512 algorithm bits, found {}", algorithm_line.len())));
+        }
+        let algorithm = algorithm_line.chars().map(|c| c == '#').collect();
+
+        // The image starts two lines down, after the algorithm and the blank line.
+        let image = Grid::parse(&lines[2..].join("\n"), |c| match c {
+            '#' => Ok(1),
+            '.' => Ok(0),
+            _ => Err(String::from("expected '#' or '.'")),
+        })
+        .map_err(|e| match e.line {
+            Some(line) => e.on_line(line + 2),
+            None => e,
+        })?;
+
+        // Surround the image with enough space for all iterations.
+        let data = Grid::from_fn(image.width() + 2 * buffer_size, image.height() + 2 * buffer_size, |(i, j)| {
+            match (i.checked_sub(buffer_size), j.checked_sub(buffer_size)) {
+                (Some(i), Some(j)) => image.get((i, j)).copied().unwrap_or(3),
+                _ => 3,
+            }
+        });
 
-        Self {
+        Ok(Self {
             algorithm,
             data,
             buffering: max_iters,
             inf_value: 0,
-        }
+        })
     }
 }
 
@@ -179,17 +195,29 @@ mod tests {
     #[test]
     fn test_data() {
         let data = include_str!("data/test_day20.dat");
-        let mut scan_data = ScannerData::new(data, 50);
-        let mut after_2 = 0;
-        let mut final_count = 0;
-        for i in 1..=50 {
-            if i == 2 {
-                after_2 = scan_data.enhance();
-            } else {
-                final_count = scan_data.enhance();
-            }
-        }
+        let mut scan_data = ScannerData::new(data, 50).unwrap();
+        let after_2 = scan_data.enhance_n(2);
+        let final_count = scan_data.enhance_n(48);
         assert_eq!(after_2, 35);
         assert_eq!(final_count, 3351);
     }
+
+    #[test]
+    fn test_frames() {
+        let scan_data = ScannerData::new(include_str!("data/test_day20.dat"), 2).unwrap();
+        let frames = Day {}.frames(&scan_data).unwrap();
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[0].picture, "#..#.\n#....\n##..#\n..#..\n..###");
+        assert_eq!(frames[2].picture.lines().count(), 9);
+        assert_eq!(frames[2].picture.matches('#').count(), 35);
+    }
+
+    #[test]
+    fn test_bad_data() {
+        let algorithm = "#.".repeat(256);
+        let err = ScannerData::new(&format!("{}\n\n#..#.\n#.o..", algorithm), 2).err().unwrap();
+        assert_eq!((err.line, err.column), (Some(4), Some(3)));
+        let err = ScannerData::new(&format!("{}#\n\n#..#.", algorithm), 2).err().unwrap();
+        assert_eq!(err.line, Some(1));
+    }
 }
@@ -1,36 +1,54 @@
-use crate::{DayResult, DaySolver};
-use std::time::SystemTime;
+use crate::{parse_at, Answer, DayError, DayInfo, PartResult, Solution};
+use std::convert::TryFrom;
 
 // The maximum hash for a game = 21 * 21 * 10 * 10 (scores x pawn locations x player turn).
 const GAMEHASHMAX: usize = 44100;
 
 pub struct Day {}
 
-impl DaySolver for Day {
-    fn solve(&self) -> DayResult {
-        let start = SystemTime::now();
+pub const INFO: DayInfo = DayInfo { day: 21, title: "Dirac Dice" };
 
-        let winning = play(10, 6, 1, 100);
+impl Solution for Day {
+    // The starting positions of the two players.
+    type Parsed = (usize, usize);
 
+    fn bundled_input(&self) -> &'static str {
+        include_str!("data/day21.dat")
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        starting_positions(input)
+    }
+
+    fn part1(&self, &(p1, p2): &Self::Parsed) -> Result<PartResult, DayError> {
+        let winning = play(10, p1, p2, 100);
+        Ok(PartResult::new(Answer::try_from(winning)?, format!("Winning combination to 1000 is {}.", winning)))
+    }
+
+    fn part2(&self, &(p1, p2): &Self::Parsed) -> Result<PartResult, DayError> {
         let mut dirac = DiracGame::new();
-        let init_state = GameState::new(6, 1);
+        let init_state = GameState::new(p1 as u8, p2 as u8);
         let wins = dirac.play(&init_state);
         let best_wins = if wins.0 > wins.1 { wins.0 } else { wins.1 };
+        let description = format!("Dirac universe wins are {} vs {}", wins.0, wins.1);
+        Ok(PartResult::new(Answer::try_from(best_wins)?, description).with_metric("cache_hits", dirac.cache_hits))
+    }
+}
 
-        let timed = SystemTime::now().duration_since(start).unwrap();
-        let description = format!(
-            "Winning combination to 1000 is {}. Dirac universe wins are \
-        {} vs {}",
-            winning, wins.0, wins.1
-        );
-
-        DayResult {
-            description,
-            part1: format!("{}", winning),
-            part2: format!("{}", best_wins),
-            timing_us: timed.as_micros(),
+// Read the starting positions, given as 'Player N starting position: P'.
+fn starting_positions(data: &str) -> Result<(usize, usize), DayError> {
+    let mut positions = vec![];
+    for (i, l) in data.lines().enumerate() {
+        let position = parse_at::<usize>(l.rsplit(' ').next().unwrap_or(l), i + 1, l)?;
+        if !(1..=10).contains(&position) {
+            return Err(DayError::at(i + 1, 1, format!("position {} is off the board", position)));
         }
+        positions.push(position);
+    }
+    if positions.len() != 2 {
+        return Err(DayError::new("expected starting positions for two players"));
     }
+    Ok((positions[0], positions[1]))
 }
 
 struct GameState {
@@ -68,12 +86,16 @@ impl GameState {
 struct DiracGame {
     // The cache returns (Player 1 wins, Player 2 wins). (0,0) is undecided.
     second_cache: Vec<(u128, u128)>,
+    cache_hits: u64,
 }
 
 impl DiracGame {
     fn new() -> Self {
         let second_cache = vec![(0u128, 0u128); GAMEHASHMAX];
-        Self { second_cache }
+        Self {
+            second_cache,
+            cache_hits: 0,
+        }
     }
 
     fn play(&mut self, state: &GameState) -> (u128, u128) {
@@ -88,6 +110,7 @@ impl DiracGame {
         // Check for a cache hit:
         let cached = self.second_cache[state.hash()];
         if cached != (0, 0) {
+            self.cache_hits += 1;
             if state.player == 0 {
                 return cached;
             } else {
@@ -180,6 +203,14 @@ fn play(board_size: usize, p1: usize, p2: usize, die_size: usize) -> u128 {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_starting_positions() {
+        let data = "Player 1 starting position: 4\nPlayer 2 starting position: 8";
+        assert_eq!(starting_positions(data), Ok((4, 8)));
+        let err = starting_positions("Player 1 starting position: 4\nPlayer 2 starting position: x").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(2), Some(29)));
+    }
+
     #[test]
     fn test_data() {
         assert_eq!(play(10, 4, 8, 100), 739785);
@@ -1,31 +1,28 @@
-use crate::{DayResult, DaySolver};
-use std::time::SystemTime;
+use crate::{checkpoint, lines, trace, DayError, DayInfo, Level, PartResult, Progress, Solution};
 
 pub struct Day {}
 
-impl DaySolver for Day {
-    fn solve(&self) -> DayResult {
-        let data = include_str!("data/day22.dat");
-        let start = SystemTime::now();
-
-        let init_cubes = cuboids_from(data, 50);
-        let init_lit = combine_cuboids(&init_cubes);
-        let all_cubes = cuboids_from(data, 0);
-        let all_lit = combine_cuboids(&all_cubes);
-
-        let timed = SystemTime::now().duration_since(start).unwrap();
-        let description = format!(
-            "On initialisation {} cubes are lit. Extending to \
-        the full array {} cubes are lit.",
-            init_lit, all_lit
-        );
-
-        DayResult {
-            description,
-            part1: format!("{}", init_lit),
-            part2: format!("{}", all_lit),
-            timing_us: timed.as_micros(),
-        }
+pub const INFO: DayInfo = DayInfo { day: 22, title: "Reactor Reboot" };
+
+impl Solution for Day {
+    type Parsed = Vec<Cuboid>;
+
+    fn bundled_input(&self) -> &'static str {
+        include_str!("data/day22.dat")
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        cuboids_from(input)
+    }
+
+    fn part1(&self, cuboids: &Self::Parsed) -> Result<PartResult, DayError> {
+        let init_lit = combine_cuboids(&limit_to(cuboids, 50))?;
+        Ok(PartResult::new(init_lit, format!("On initialisation {} cubes are lit.", init_lit)))
+    }
+
+    fn part2(&self, cuboids: &Self::Parsed) -> Result<PartResult, DayError> {
+        let all_lit = combine_cuboids(cuboids)?;
+        Ok(PartResult::new(all_lit, format!("Extending to the full array {} cubes are lit.", all_lit)))
     }
 }
 
@@ -43,7 +40,7 @@ impl Point {
 }
 
 #[derive(Debug, Clone)]
-struct Cuboid {
+pub struct Cuboid {
     from: Point,
     to: Point,
     status: bool,
@@ -51,11 +48,13 @@ struct Cuboid {
 }
 
 impl Cuboid {
-    // Note the '+1' as cuboid boundaries are inclusive.
-    fn enclosed_cubes(&self) -> i64 {
-        (1 + self.to.x - self.from.x)
-            * (1 + self.to.y - self.from.y)
-            * (1 + self.to.z - self.from.z)
+    // Note the '+1' as cuboid boundaries are inclusive. Worked out in i128, as the sides of a
+    // valid cuboid can be long enough for the volume to overflow i64. None if even that isn't enough.
+    fn enclosed_cubes(&self) -> Option<i128> {
+        let side = |from: i64, to: i64| 1 + to as i128 - from as i128;
+        side(self.from.x, self.to.x)
+            .checked_mul(side(self.from.y, self.to.y))?
+            .checked_mul(side(self.from.z, self.to.z))
     }
 }
 
@@ -75,9 +74,10 @@ fn intersect_lines(from1: i64, to1: i64, from2: i64, to2: i64) -> Option<(i64, i
     Some((from2, std::cmp::min(to1, to2)))
 }
 
-fn combine_cuboids(cuboids: &[Cuboid]) -> i128 {
+fn combine_cuboids(cuboids: &[Cuboid]) -> Result<i128, DayError> {
     let mut combined = vec![];
-    for cuboid in cuboids {
+    for (i, cuboid) in cuboids.iter().enumerate() {
+        checkpoint(Progress::of(i, cuboids.len(), "cuboids"))?;
         // We need to try to combine with all existing cuboids.
         let mut to_add = vec![];
         for existing in &combined {
@@ -91,20 +91,23 @@ fn combine_cuboids(cuboids: &[Cuboid]) -> i128 {
             to_add.push(cuboid.clone());
         }
         combined.append(&mut to_add);
-        //println!("{} lit.", volume_of(&combined));
+        trace!(Level::Debug, INFO.day, "{} lit.", volume_of(&combined)?);
     }
 
     volume_of(&combined)
 }
 
-fn volume_of(combined: &[Cuboid]) -> i128 {
+fn volume_of(combined: &[Cuboid]) -> Result<i128, DayError> {
     // Find the total volume.
-    let mut total_volume = 0;
+    let mut total_volume = 0i128;
     for c in combined {
-        total_volume += (c.contribution * c.enclosed_cubes()) as i128
+        total_volume = c
+            .enclosed_cubes()
+            .and_then(|cubes| total_volume.checked_add(c.contribution as i128 * cubes))
+            .ok_or_else(|| DayError::new("too many cubes to count"))?;
     }
 
-    total_volume
+    Ok(total_volume)
 }
 
 // When two cuboids intersect, we can represent the union as a number of disjoint cuboids.
@@ -150,68 +153,84 @@ fn intersect_cuboids(c1: &Cuboid, c2: &Cuboid) -> Option<Cuboid> {
     }
 }
 
-fn cuboids_from(data: &str, limit: i64) -> Vec<Cuboid> {
+// Each line is 'on' or 'off' then the cuboid, as 'x=a..b,y=c..d,z=e..f'.
+fn cuboids_from(data: &str) -> Result<Vec<Cuboid>, DayError> {
     let mut cubes = vec![];
-    for l in data.lines() {
-        let l_wspace = l.split_whitespace().collect::<Vec<&str>>();
-        let status = l_wspace[0] == "on";
+    for line in lines(data) {
+        let (status, ranges) = line.split_once(line.text, " ", "on|off x=..,y=..,z=..")?;
+        let status = match status {
+            "on" => true,
+            "off" => false,
+            _ => return Err(line.expected(status, "on|off")),
+        };
         let mut from = Point::origin();
         let mut to = Point::origin();
-        let coords = l_wspace[1].split(',');
-        for c in coords {
-            let coord_bounds = c.split('=').collect::<Vec<&str>>();
-            let mut min_max = coord_bounds[1]
-                .split("..")
-                .map(|s| s.parse::<i64>().unwrap())
-                .collect::<Vec<i64>>();
-            min_max.sort_unstable();
-            match coord_bounds[0] {
+        // Each axis has to be given exactly once.
+        let mut seen = [false; 3];
+        for token in ranges.split(',') {
+            let range = line.range::<i64>(token)?;
+            let (low, high) = (range.from.min(range.to), range.from.max(range.to));
+            let axis = match range.key {
                 "x" => {
-                    if limit > 0 {
-                        from.x = std::cmp::max(-limit, min_max[0]);
-                        to.x = std::cmp::min(limit, min_max[1]);
-                    } else {
-                        from.x = min_max[0];
-                        to.x = min_max[1];
-                    }
+                    from.x = low;
+                    to.x = high;
+                    0
                 }
                 "y" => {
-                    if limit > 0 {
-                        from.y = std::cmp::max(-limit, min_max[0]);
-                        to.y = std::cmp::min(limit, min_max[1]);
-                    } else {
-                        from.y = min_max[0];
-                        to.y = min_max[1];
-                    }
+                    from.y = low;
+                    to.y = high;
+                    1
                 }
                 "z" => {
-                    if limit > 0 {
-                        from.z = std::cmp::max(-limit, min_max[0]);
-                        to.z = std::cmp::min(limit, min_max[1]);
-                    } else {
-                        from.z = min_max[0];
-                        to.z = min_max[1];
-                    }
+                    from.z = low;
+                    to.z = high;
+                    2
                 }
-                _ => panic!("Unexpected coordinate symbol {}", coord_bounds[0]),
+                _ => return Err(line.error(token, format!("unexpected coordinate symbol {}", range.key))),
+            };
+            if std::mem::replace(&mut seen[axis], true) {
+                return Err(line.error(token, format!("{} is given twice", range.key)));
             }
         }
-
-        let out_of_range = limit > 0
-            && (from.x > limit
-                || from.y > limit
-                || from.z > limit
-                || to.x < -limit
-                || to.y < -limit
-                || to.z < -limit);
+        if let Some(missing) = seen.iter().position(|seen| !seen) {
+            return Err(line.error(ranges, format!("expected a range for {}", ["x", "y", "z"][missing])));
+        }
 
         let contribution = if status { 1 } else { -1 };
+        cubes.push(Cuboid {
+            from,
+            to,
+            status,
+            contribution,
+        });
+    }
+    Ok(cubes)
+}
+
+// Clip the cuboids to the region -limit..limit on each axis, dropping any outside it.
+fn limit_to(cuboids: &[Cuboid], limit: i64) -> Vec<Cuboid> {
+    let mut cubes = vec![];
+    for cuboid in cuboids {
+        let out_of_range = cuboid.from.x > limit
+            || cuboid.from.y > limit
+            || cuboid.from.z > limit
+            || cuboid.to.x < -limit
+            || cuboid.to.y < -limit
+            || cuboid.to.z < -limit;
+
         if !out_of_range {
             cubes.push(Cuboid {
-                from,
-                to,
-                status,
-                contribution,
+                from: Point {
+                    x: std::cmp::max(-limit, cuboid.from.x),
+                    y: std::cmp::max(-limit, cuboid.from.y),
+                    z: std::cmp::max(-limit, cuboid.from.z),
+                },
+                to: Point {
+                    x: std::cmp::min(limit, cuboid.to.x),
+                    y: std::cmp::min(limit, cuboid.to.y),
+                    z: std::cmp::min(limit, cuboid.to.z),
+                },
+                ..cuboid.clone()
             });
         }
     }
@@ -226,11 +245,30 @@ mod tests {
     fn test_data() {
         let data = include_str!("data/test_day22b.dat");
 
-        let init_cubes = cuboids_from(data, 50);
-        let init_lit = combine_cuboids(&init_cubes);
+        let all_cubes = cuboids_from(data).unwrap();
+        let init_lit = combine_cuboids(&limit_to(&all_cubes, 50)).unwrap();
         assert_eq!(init_lit, 474140);
-        let all_cubes = cuboids_from(data, 0);
-        let all_lit = combine_cuboids(&all_cubes);
+        let all_lit = combine_cuboids(&all_cubes).unwrap();
         assert_eq!(all_lit, 2758514936282235);
     }
+
+    #[test]
+    fn test_bad_data() {
+        let err = cuboids_from("on x=10..12,y=10..12,z=10..12\non x=11..13,w=11..13,z=11..13").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(2), Some(13)));
+        let err = cuboids_from("toggle x=10..12,y=10..12,z=10..12").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(1), Some(1)));
+        let err = cuboids_from("on x=1..2,y=1..2").unwrap_err();
+        assert_eq!((err.column, err.reason.as_str()), (Some(4), "expected a range for z"));
+        let err = cuboids_from("on x=1..2,x=3..4,y=1..2,z=1..2").unwrap_err();
+        assert_eq!((err.column, err.reason.as_str()), (Some(11), "x is given twice"));
+    }
+
+    #[test]
+    fn test_large_cuboids() {
+        let cuboids = cuboids_from("on x=0..3000000,y=0..3000000,z=0..3000000").unwrap();
+        assert_eq!(combine_cuboids(&cuboids).unwrap(), 3000001i128.pow(3));
+        let widest = format!("on x={0}..{1},y={0}..{1},z={0}..{1}", i64::MIN, i64::MAX);
+        assert_eq!(combine_cuboids(&cuboids_from(&widest).unwrap()).unwrap_err().reason, "too many cubes to count");
+    }
 }
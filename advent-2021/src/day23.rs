@@ -1,41 +1,84 @@
 use crate::day23::Contents::Empty;
-use crate::{DayResult, DaySolver};
+use crate::{checkpoint, DayError, DayInfo, Frame, PartResult, Progress, Solution, Visualize};
 use std::cmp::{Ord, Ordering, Reverse};
 use std::collections::{BinaryHeap, HashMap};
 use std::fmt;
-use std::time::SystemTime;
 
 pub struct Day {}
 
-impl DaySolver for Day {
-    fn solve(&self) -> DayResult {
-        let start = SystemTime::now();
+pub const INFO: DayInfo = DayInfo { day: 23, title: "Amphipod" };
 
-        let burrows = Burrows::mine();
-        let unfolded_burrows = burrows.unfold();
+impl Solution for Day {
+    type Parsed = Burrows;
+
+    fn bundled_input(&self) -> &'static str {
+        include_str!("data/day23.dat")
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        Burrows::from_diagram(input)
+    }
+
+    fn part1(&self, burrows: &Self::Parsed) -> Result<PartResult, DayError> {
+        let (best_cost, search) = least_energy(burrows)?;
+        Ok(search.report(PartResult::new(best_cost, format!("Can get all the amphipods back for a cost of {}.", best_cost))))
+    }
+
+    fn part2(&self, burrows: &Self::Parsed) -> Result<PartResult, DayError> {
+        let (best_cost_unfolded, search) = least_energy(&burrows.unfold())?;
+        Ok(search.report(PartResult::new(best_cost_unfolded,
+                                         format!("After unfolding, minimum energy is {}", best_cost_unfolded))))
+    }
 
-        let (best_cost, _best_moves) = find_best_moves(&burrows);
-        let (best_cost_unfolded, _best_moves_unfolded) = find_best_moves(&unfolded_burrows);
-
-        let timed = SystemTime::now().duration_since(start).unwrap();
-        let description = format!(
-            "Can get all the amphipods back for a cost of {}. After \
-        unfolding, minimum energy is {}",
-            best_cost, best_cost_unfolded
-        );
-
-        DayResult {
-            description,
-            part1: format!("{}", best_cost),
-            part2: format!("{}", best_cost_unfolded),
-            timing_us: timed.as_micros(),
+    fn visualizer(&self) -> Option<&dyn Visualize<Parsed = Self::Parsed>> {
+        Some(self)
+    }
+}
+
+// Each move of the cheapest way home, for the folded burrows.
+impl Visualize for Day {
+    fn frames(&self, burrows: &Burrows) -> Result<Vec<Frame>, DayError> {
+        // Nothing watches a visualisation, so the search runs to the end.
+        let (_, best_moves) = find_best_moves(burrows, &mut SearchCounts::default(), true)?;
+        let mut frames = vec![Frame::new("The starting positions", burrows)];
+        let mut state = burrows.clone();
+        for (i, m) in best_moves.iter().enumerate() {
+            state = move_pod(&state, m, false);
+            frames.push(Frame::new(format!("Move {} of {}: {}", i + 1, best_moves.len(), m), &state));
         }
+        Ok(frames)
+    }
+}
+
+fn least_energy(burrows: &Burrows) -> Result<(u64, SearchCounts), DayError> {
+    let mut search = SearchCounts::default();
+    let (best_cost, _best_moves) = find_best_moves(burrows, &mut search, false)?;
+    Ok((best_cost, search))
+}
+
+// How much work the search for the best moves did.
+#[derive(Debug, Default)]
+struct SearchCounts {
+    // States put on the heap to try.
+    pushed: u64,
+    // States taken off the heap but dropped, as they'd been reached more cheaply already.
+    skipped: u64,
+    // States whose moves were tried.
+    explored: u64,
+}
+
+impl SearchCounts {
+    fn report(&self, result: PartResult) -> PartResult {
+        result
+            .with_metric("states_pushed", self.pushed)
+            .with_metric("states_skipped", self.skipped)
+            .with_metric("states_explored", self.explored)
     }
 }
 
 // The burrow structure is quite complicated.
-#[derive(Eq)]
-struct Burrows {
+#[derive(Clone, Eq)]
+pub struct Burrows {
     // There are four burrows, two deep. These are indexed.
     burrows: [Vec<Contents>; 4],
     // There are seven places above where an amphipod may stop.
@@ -143,23 +186,37 @@ impl Burrows {
         }
     }
 
-    fn mine() -> Self {
-        Burrows::new([
-            vec![Contents::B, Contents::D],
-            vec![Contents::C, Contents::D],
-            vec![Contents::C, Contents::A],
-            vec![Contents::B, Contents::A],
-        ])
-    }
-
-    #[cfg(test)]
-    fn test() -> Self {
-        Burrows::new([
-            vec![Contents::B, Contents::A],
-            vec![Contents::C, Contents::D],
-            vec![Contents::B, Contents::C],
-            vec![Contents::D, Contents::A],
-        ])
+    // Read the starting burrows from the puzzle diagram. Each burrow row lists one pod per burrow.
+    fn from_diagram(data: &str) -> Result<Self, DayError> {
+        let mut burrows = [vec![], vec![], vec![], vec![]];
+        for (i, line) in data.lines().enumerate().skip(2) {
+            if let Some(j) = line.find(|c| !matches!(c, 'A'..='D' | '#' | '.' | ' ')) {
+                return Err(DayError::at(i + 1, j + 1, "expected an amphipod A-D"));
+            }
+            let pods = line
+                .chars()
+                .filter_map(|c| match c {
+                    'A' => Some(Contents::A),
+                    'B' => Some(Contents::B),
+                    'C' => Some(Contents::C),
+                    'D' => Some(Contents::D),
+                    _ => None,
+                })
+                .collect::<Vec<Contents>>();
+            if pods.is_empty() {
+                continue;
+            }
+            if pods.len() != 4 {
+                return Err(DayError::at(i + 1, 1, format!("expected 4 amphipods, found {}", pods.len())));
+            }
+            for (b, pod) in pods.iter().enumerate() {
+                burrows[b].push(*pod);
+            }
+        }
+        if burrows[0].len() != 2 {
+            return Err(DayError::new(format!("expected burrows two deep, found {}", burrows[0].len())));
+        }
+        Ok(Burrows::new(burrows))
     }
 
     fn unfold(&self) -> Self {
@@ -356,24 +413,27 @@ impl Burrows {
     }
 }
 
-fn find_best_moves(from: &Burrows) -> (u64, Vec<AMove>) {
+// The least energy needed to get the amphipods home. With `caching`, the moves that do it too.
+fn find_best_moves(from: &Burrows, search: &mut SearchCounts, caching: bool) -> Result<(u64, Vec<AMove>), DayError> {
     let mut seen_states = HashMap::new();
     let mut working = BinaryHeap::new();
     for b in from
         .available_moves()
         .iter()
-        .map(|m| move_pod(from, m, false))
+        .map(|m| move_pod(from, m, caching))
     {
         working.push(Reverse(b));
+        search.pushed += 1;
     }
 
-    let mut best = u64::max_value();
+    let mut best = u64::MAX;
     let mut best_moves = vec![];
 
     while !working.is_empty() {
         let try_state = working.pop().unwrap().0;
 
         if seen_states.contains_key(&try_state.state()) {
+            search.skipped += 1;
             continue;
         }
         seen_states.insert(try_state.state(), true);
@@ -391,16 +451,22 @@ fn find_best_moves(from: &Burrows) -> (u64, Vec<AMove>) {
         }
 
         // Otherwise, lets try all the moves from here.
+        search.explored += 1;
+        checkpoint(Progress::count(search.explored, "states explored"))?;
         for next in try_state
             .available_moves()
             .iter()
-            .map(|m| move_pod(&try_state, m, false))
+            .map(|m| move_pod(&try_state, m, caching))
         {
             working.push(Reverse(next));
+            search.pushed += 1;
         }
     }
 
-    (best, best_moves)
+    if best == u64::MAX {
+        return Err(DayError::new("the amphipods can't all get home"));
+    }
+    Ok((best, best_moves))
 }
 
 fn move_pod(state: &Burrows, m: &AMove, caching: bool) -> Burrows {
@@ -512,12 +578,33 @@ mod tests {
 
     #[test]
     fn test_data() {
-        let burrows = Burrows::test();
+        let data = include_str!("data/test_day23.dat");
+        let burrows = Burrows::from_diagram(data).unwrap();
         let unfolded_burrows = burrows.unfold();
 
-        let (best_cost, _best_moves) = find_best_moves(&burrows);
-        let (best_cost_unfolded, _best_moves_unfolded) = find_best_moves(&unfolded_burrows);
+        let mut search = SearchCounts::default();
+        let (best_cost, _best_moves) = find_best_moves(&burrows, &mut search, false).unwrap();
+        let (best_cost_unfolded, _best_moves_unfolded) =
+            find_best_moves(&unfolded_burrows, &mut SearchCounts::default(), false).unwrap();
         assert_eq!(best_cost, 12521);
         assert_eq!(best_cost_unfolded, 44169);
+        assert!(search.pushed > search.explored + search.skipped);
+    }
+
+    #[test]
+    fn test_bad_data() {
+        let err = Burrows::from_diagram("#############\n#...........#\n###B#C#B#D###\n  #A#E#C#A#\n  #########").err().unwrap();
+        assert_eq!((err.line, err.column), (Some(4), Some(6)));
+        let err = Burrows::from_diagram("#############\n#...........#\n###B#C#B###\n  #A#D#C#A#\n  #########").err().unwrap();
+        assert_eq!((err.line, err.column), (Some(3), Some(1)));
+    }
+
+    #[test]
+    fn test_no_way_home() {
+        // Three amphipods of type A can't share a burrow two deep.
+        let burrows = Burrows::from_diagram("#############\n#...........#\n###A#A#B#C###\n  #A#B#C#D#\n  #########").unwrap();
+        let err = Day {}.frames(&burrows).unwrap_err();
+        assert_eq!(err.reason, "the amphipods can't all get home");
+        assert_eq!(least_energy(&burrows).unwrap_err(), err);
     }
 }
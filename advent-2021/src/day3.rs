@@ -1,51 +1,88 @@
 use std::cmp::Ordering;
-use std::time::SystemTime;
-use crate::{DayResult, DaySolver};
+use crate::{DayError, DayInfo, PartResult, Solution};
 
 pub struct Day {}
 
-impl DaySolver for Day {
-    fn solve(&self) -> DayResult {
-        let data = include_str!("data/day3.dat");
-        let start = SystemTime::now();
-        let (epsilon, gamma) = epsilon_gamma(data, 12);
-        let o2 = o2_generator(data, 12);
-        let co2 = co2_scrubber(data, 12);
-        let timed = SystemTime::now().duration_since(start).unwrap();
+pub const INFO: DayInfo = DayInfo { day: 3, title: "Binary Diagnostic" };
+
+impl Solution for Day {
+    // The report, and the width of each number in it.
+    type Parsed = (String, usize);
+
+    fn bundled_input(&self) -> &'static str {
+        include_str!("data/day3.dat")
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        let width = report_width(input)?;
+        Ok((String::from(input), width))
+    }
+
+    fn part1(&self, (data, width): &Self::Parsed) -> Result<PartResult, DayError> {
+        let (epsilon, gamma) = epsilon_gamma(data, *width)?;
         let desc1 = format!("Analyzed logs. Epsilon = {}, Gamma = {} -> Power {}",
                             epsilon, gamma,
                             epsilon * gamma);
+        Ok(PartResult::new(epsilon * gamma, desc1))
+    }
+
+    fn part2(&self, (data, width): &Self::Parsed) -> Result<PartResult, DayError> {
+        let o2 = o2_generator(data, *width)?;
+        let co2 = co2_scrubber(data, *width)?;
         let desc2 = format!("O2 Generator {}, CO2 Scrubber {} -> Life Support Rating {}",
                             o2, co2,
                             o2 * co2);
-
-        DayResult{
-            description: format!("{}\n{}", desc1, desc2),
-            part1: format!("{}", epsilon * gamma),
-            part2: format!("{}", co2 * o2),
-            timing_us: timed.as_micros(),
-        }
+        Ok(PartResult::new(co2 * o2, desc2))
     }
 }
 
+// Kept where the tests were first written, above the diagnostics.
 #[cfg(test)]
+#[allow(clippy::items_after_test_module)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_data() {
         let data = include_str!("data/test_day3.dat");
-        let (epsilon, gamma) = epsilon_gamma(data, 5);
-        let o2 = o2_generator(data, 5);
-        let co2 = co2_scrubber(data, 5);
+        let width = report_width(data).unwrap();
+        assert_eq!(width, 5);
+        let (epsilon, gamma) = epsilon_gamma(data, width).unwrap();
+        let o2 = o2_generator(data, width).unwrap();
+        let co2 = co2_scrubber(data, width).unwrap();
         assert_eq!(epsilon, 9);
         assert_eq!(gamma, 22);
         assert_eq!(o2, 23);
         assert_eq!(co2, 10);
     }
+
+    #[test]
+    fn test_bad_data() {
+        let err = report_width("00100\n11120").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(2), Some(4)));
+        assert!(report_width("00100\n1110").is_err());
+        assert!(epsilon_gamma("01\n10", 2).is_err());
+    }
+}
+
+// Check the report is made of equal length binary numbers, and find that length.
+fn report_width(data: &str) -> Result<usize, DayError> {
+    let width = data.lines().next().map(|l| l.len()).unwrap_or(0);
+    if width == 0 {
+        return Err(DayError::new("empty diagnostic report"));
+    }
+    for (i, line) in data.lines().enumerate() {
+        if let Some(col) = line.find(|c| c != '0' && c != '1') {
+            return Err(DayError::at(i + 1, col + 1, "expected a binary digit"));
+        }
+        if line.len() != width {
+            return Err(DayError::at(i + 1, 1, format!("expected {} digits, found {}", width, line.len())));
+        }
+    }
+    Ok(width)
 }
 
-pub fn epsilon_gamma(data :&str, length: usize) -> (i32, i32) {
+pub fn epsilon_gamma(data :&str, length: usize) -> Result<(i32, i32), DayError> {
     let lines = data.lines();
     let mut line_count = 0;
     let mut counts = vec![0;length];
@@ -63,9 +100,11 @@ pub fn epsilon_gamma(data :&str, length: usize) -> (i32, i32) {
     let mut epsilon = 0;
     let mut power = 1;
 
-    for bit in counts.iter().rev() {
+    for (i, bit) in counts.iter().rev().enumerate() {
         match (2*bit).cmp(&line_count) {
-            Ordering::Equal => panic!("Exactly balanced chars."),
+            Ordering::Equal => {
+                return Err(DayError::new(format!("bit {} is exactly balanced between 0 and 1", length - i)))
+            },
             // 1 is most common, so gets added to the gamma
             Ordering::Greater => gamma += power,
             // 1 is least common, so gets added to epsilon
@@ -75,10 +114,10 @@ pub fn epsilon_gamma(data :&str, length: usize) -> (i32, i32) {
         power *= 2;
     }
 
-    (epsilon, gamma)
+    Ok((epsilon, gamma))
 }
 
-pub fn co2_scrubber(data: &str, length: usize) -> i32 {
+pub fn co2_scrubber(data: &str, length: usize) -> Result<i32, DayError> {
     let mut lines: Vec<&str> = data.lines().collect();
 
     for i in 0..length {
@@ -101,14 +140,14 @@ pub fn co2_scrubber(data: &str, length: usize) -> i32 {
         }
 
         if lines.len() == 1 {
-            return int_of_str(lines[0]);
+            return Ok(int_of_str(lines[0]));
         }
     }
 
-    panic!("did not find a single value");
+    Err(DayError::new("CO2 scrubber rating did not narrow to a single value"))
 }
 
-pub fn o2_generator(data: &str, length: usize) -> i32 {
+pub fn o2_generator(data: &str, length: usize) -> Result<i32, DayError> {
     let mut lines: Vec<&str> = data.lines().collect();
 
     for i in 0..length {
@@ -133,11 +172,11 @@ pub fn o2_generator(data: &str, length: usize) -> i32 {
 
         if lines.len() == 1 {
             let val = int_of_str(lines[0]);
-            return val;
+            return Ok(val);
         }
     }
 
-    panic!("did not find a single value");
+    Err(DayError::new("O2 generator rating did not narrow to a single value"))
 }
 
 fn int_of_str(s: &str) -> i32 {
@@ -152,4 +191,3 @@ fn int_of_str(s: &str) -> i32 {
 
     res
 }
-
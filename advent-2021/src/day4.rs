@@ -1,26 +1,30 @@
-use std::time::SystemTime;
 use std::collections::HashMap;
-use crate::{DayResult, DaySolver};
+use crate::{parse_at, trace, DayError, DayInfo, Level, PartResult, Solution};
 
 pub struct Day {}
 
-impl DaySolver for Day {
-    fn solve(&self) -> DayResult {
-        let test_data = include_str!("./data/day4.dat");
-        let mut game = BingoGame::from_input(test_data);
-        let start = SystemTime::now();
-        let (first, last) = game.play();
-        let timed = SystemTime::now().duration_since(start).unwrap();
-        let description = format!("First winning score is {}, last winning score is {}",
-                 first,
-                 last);
-
-        DayResult{
-            description,
-            part1: format!("{}", first),
-            part2: format!("{}", last),
-            timing_us: timed.as_micros(),
-        }
+pub const INFO: DayInfo = DayInfo { day: 4, title: "Giant Squid" };
+
+impl Solution for Day {
+    type Parsed = BingoGame;
+
+    fn bundled_input(&self) -> &'static str {
+        include_str!("data/day4.dat")
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        BingoGame::from_input(input)
+    }
+
+    // Each part plays its own copy of the game through.
+    fn part1(&self, game: &Self::Parsed) -> Result<PartResult, DayError> {
+        let (first, _) = game.clone().play();
+        Ok(PartResult::new(first, format!("First winning score is {}", first)))
+    }
+
+    fn part2(&self, game: &Self::Parsed) -> Result<PartResult, DayError> {
+        let (_, last) = game.clone().play();
+        Ok(PartResult::new(last, format!("Last winning score is {}", last)))
     }
 }
 
@@ -31,14 +35,21 @@ mod tests {
     #[test]
     fn test_data() {
         let test_data = include_str!("./data/test_day4.dat");
-        let mut game = BingoGame::from_input(test_data);
+        let mut game = BingoGame::from_input(test_data).unwrap();
         let (first, last) = game.play();
         assert_eq!(first, 4512);
         assert_eq!(last, 1924);
     }
+
+    #[test]
+    fn test_bad_data() {
+        let err = BingoGame::from_input("7,4,9\n\n22 13 17\n 8  2 x3").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(4), Some(7)));
+        assert!(BingoGame::from_input("7,4,9").is_err());
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Board {
     vals_to_row: HashMap<i32, i32>,
     vals_to_col: HashMap<i32, i32>,
@@ -81,9 +92,9 @@ impl Board {
         let called_in_row = self.called_in_row.entry(row).or_insert(0);
         *called_in_row += 1;
 
-        //println!("Called: {} at R/C {}/{}. Col completion {}/{}. Row completion {}/{}",
-        //    val, row, col, called_in_col, self.rows, called_in_row, self.cols
-        //);
+        trace!(Level::Trace, INFO.day, "Called: {} at R/C {}/{}. Col completion {}/{}. Row completion {}/{}",
+            val, row, col, called_in_col, self.rows, called_in_row, self.cols
+        );
 
         if *called_in_col == self.rows || *called_in_row == self.cols {
             // Work out the score and return that.
@@ -99,8 +110,8 @@ impl Board {
 
 }
 
-#[derive(Debug)]
-struct BingoGame {
+#[derive(Debug, Clone)]
+pub struct BingoGame {
     boards: Vec<Board>,
     calls: Vec<i32>,
 }
@@ -135,18 +146,20 @@ impl BingoGame {
         (first_score, last_score)
     }
 
-    fn from_input(inp: &str) -> Self {
+    fn from_input(inp: &str) -> Result<Self, DayError> {
         // The initial row is the calls, the remainder are the boards.
         let mut parse_mode = ParsingMode::ParseCalls;
         let mut calls = vec![];
         let mut current_board = Board::empty();
         let mut boards = vec![];
         let mut row = 0;
-        for line in inp.lines() {
+        for (i, line) in inp.lines().enumerate() {
             match parse_mode {
                 ParsingMode::ParseCalls => {
                     // We expect a line of comma separated calls.
-                    calls = line.split(',').map(|x|  x.parse::<i32>().unwrap()).collect();
+                    calls = line.split(',')
+                        .map(|x| parse_at::<i32>(x.trim(), i + 1, line))
+                        .collect::<Result<Vec<i32>, DayError>>()?;
                     parse_mode = ParsingMode::ParseBoard;
                 },
                 ParsingMode::ParseBoard => {
@@ -160,7 +173,10 @@ impl BingoGame {
                         row = 0;
                     } else {
                         // We add to the current board.
-                        for (col, val) in parts.iter().map(|x| x.parse::<i32>().unwrap()).enumerate() {
+                        let vals = parts.iter()
+                            .map(|x| parse_at::<i32>(x, i + 1, line))
+                            .collect::<Result<Vec<i32>, DayError>>()?;
+                        for (col, val) in vals.into_iter().enumerate() {
                             let to_call = current_board.vals_to_call.entry(val).or_insert(0);
                             *to_call += 1;
                             current_board.vals_to_row.insert(val, row);
@@ -181,9 +197,13 @@ impl BingoGame {
             boards.push(current_board);
         }
 
-        BingoGame {
+        if boards.is_empty() {
+            return Err(DayError::new("no bingo boards found"));
+        }
+
+        Ok(BingoGame {
             calls,
             boards,
-        }
+        })
     }
 }
\ No newline at end of file
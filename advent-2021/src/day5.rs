@@ -1,27 +1,31 @@
-use std::time::SystemTime;
 use std::cmp::{min, max};
 use std::collections::HashMap;
-use std::str::FromStr;
-use crate::{DayResult, DaySolver};
+use crate::{lines, DayError, DayInfo, Line, PartResult, Solution};
 
 pub struct Day {}
 
-impl DaySolver for Day {
-    fn solve(&self) -> DayResult {
-        let data = include_str!("./data/day5.dat");
-        let start = SystemTime::now();
-        let connections: Vec<Connection> = data.lines().map(|s| s.parse::<Connection>().unwrap()).collect();
-        let danger_points = crossings(&connections, false);
-        let diagonal_danger_points = crossings(&connections, true);
-        let timed = SystemTime::now().duration_since(start).unwrap();
-        let desc1 = format!("There are {} danger points.", danger_points);
-        let desc2 = format!("There are {} diagonal danger points.", diagonal_danger_points);
-        DayResult{
-            description: format!("{}\n{}", desc1, desc2),
-            part1: format!("{}", danger_points),
-            part2: format!("{}", diagonal_danger_points),
-            timing_us: timed.as_micros(),
-        }
+pub const INFO: DayInfo = DayInfo { day: 5, title: "Hydrothermal Venture" };
+
+impl Solution for Day {
+    type Parsed = Vec<Connection>;
+
+    fn bundled_input(&self) -> &'static str {
+        include_str!("data/day5.dat")
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        connections(input)
+    }
+
+    fn part1(&self, connections: &Self::Parsed) -> Result<PartResult, DayError> {
+        let danger_points = crossings(connections, false);
+        Ok(PartResult::new(danger_points, format!("There are {} danger points.", danger_points)))
+    }
+
+    fn part2(&self, connections: &Self::Parsed) -> Result<PartResult, DayError> {
+        let diagonal_danger_points = crossings(connections, true);
+        Ok(PartResult::new(diagonal_danger_points,
+                           format!("There are {} diagonal danger points.", diagonal_danger_points)))
     }
 }
 
@@ -33,15 +37,35 @@ mod tests {
     #[test]
     fn test_data() {
         let data = include_str!("./data/test_day5.dat");
-        let connections: Vec<Connection> = data.lines().map(|s| s.parse::<Connection>().unwrap()).collect();
+        let connections = connections(data).unwrap();
         let danger_points = crossings(&connections, false);
         assert_eq!(danger_points, 5);
         let diagonal_danger_points = crossings(&connections, true);
         assert_eq!(diagonal_danger_points, 12);
+    }
 
+    #[test]
+    fn test_bad_data() {
+        let err = connections("0,9 -> 5,9\n8,0 -> 0,x").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(2), Some(10)));
+        let err = connections("0,9 -> 5,9\n8,0 => 0,8").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(2), Some(1)));
     }
 }
 
+// Each line is a connection, given as 'x1,y1 -> x2,y2'.
+fn connections(data: &str) -> Result<Vec<Connection>, DayError> {
+    lines(data)
+        .map(|line| {
+            let (from, to) = line.split_once(line.text, " -> ", "x1,y1 -> x2,y2")?;
+            Ok(Connection {
+                from: point(&line, from)?,
+                to: point(&line, to)?,
+            })
+        })
+        .collect()
+}
+
 fn crossings(connections: &[Connection], with_diagonals: bool) -> i32 {
     let mut covered: HashMap<Point, i32> = HashMap::new();
     // We walk each connection filling its points
@@ -100,45 +124,14 @@ struct Point {
     y: i32,
 }
 
-impl FromStr for Point {
-    type Err = String;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // We expect a pair of int values
-        let parts: Vec<&str> = s.split(',').collect();
-        if parts.len() != 2 {
-            Err(format!("Could not parse {} as a point", s))
-        } else {
-            Ok(Self {
-                x: parts[0].parse::<i32>().unwrap(),
-                y: parts[1].parse::<i32>().unwrap(),
-            })
-        }
-    }
+fn point<'a>(line: &Line<'a>, token: &'a str) -> Result<Point, DayError> {
+    let [x, y] = line.ints_n(token, ',')?;
+    Ok(Point { x, y })
 }
 
 // A line between two points.
 #[derive(Debug)]
-struct Connection {
+pub struct Connection {
     from: Point,
     to: Point,
 }
-
-impl FromStr for Connection {
-    type Err = String;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // We expect a pair of int values
-        let parts: Vec<&str> = s.split(" -> ").collect();
-        if parts.len() != 2 {
-            Err(format!("Could not parse {} as a connection", s))
-        } else {
-            let from = parts[0].parse::<Point>()?;
-            let to = parts[1].parse::<Point>()?;
-            Ok(Self {
-                from,
-                to,
-            })
-        }
-    }
-}
\ No newline at end of file
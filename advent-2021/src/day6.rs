@@ -1,50 +1,75 @@
-use std::time::SystemTime;
-use crate::{DayResult, DaySolver};
+use crate::{column_of, parse_at, DayError, DayInfo, PartResult, Solution};
 
 pub struct Day {}
 
-impl DaySolver for Day {
-    fn solve(&self) -> DayResult {
-        let data = include_str!("./data/day6.dat");
-        let start = SystemTime::now();
-        let after80 = evolve(data, 80);
-        let after256 = evolve(data, 256);
-        let timed = SystemTime::now().duration_since(start).unwrap();
-        let desc1 = format!("After 80 days there are {} fish.", after80);
-        let desc2 = format!("After 256 days there are {} fish.", after256);
-
-        DayResult{
-            description: format!("{}\n{}", desc1, desc2),
-            part1: format!("{}", after80),
-            part2: format!("{}", after256),
-            timing_us: timed.as_micros(),
-        }
+pub const INFO: DayInfo = DayInfo { day: 6, title: "Lanternfish" };
+
+impl Solution for Day {
+    // The number of fish with each timer value 0-8.
+    type Parsed = [u64; 9];
+
+    fn bundled_input(&self) -> &'static str {
+        include_str!("data/day6.dat")
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        school(input)
+    }
+
+    fn part1(&self, fishes: &Self::Parsed) -> Result<PartResult, DayError> {
+        let after80 = evolve(fishes, 80);
+        Ok(PartResult::new(after80, format!("After 80 days there are {} fish.", after80)))
+    }
+
+    fn part2(&self, fishes: &Self::Parsed) -> Result<PartResult, DayError> {
+        let after256 = evolve(fishes, 256);
+        Ok(PartResult::new(after256, format!("After 256 days there are {} fish.", after256)))
     }
 }
 
+// The tests stay next to the Solution impl, where they started out.
 #[cfg(test)]
+#[allow(clippy::items_after_test_module)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_data() {
         let data = include_str!("./data/test_day6.dat");
-        let after80 = evolve(data, 80);
-        let after256 = evolve(data, 256);
+        let fishes = school(data).unwrap();
+        let after80 = evolve(&fishes, 80);
+        let after256 = evolve(&fishes, 256);
         assert_eq!(after80, 5934);
         assert_eq!(after256, 26984457539);
     }
+
+    #[test]
+    fn test_bad_data() {
+        let err = school("3,4,9,1").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(1), Some(5)));
+        let err = school("3,4,,1").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(1), Some(5)));
+    }
 }
 
-pub fn evolve(from: &str, generations: usize) -> u64 {
+fn school(from: &str) -> Result<[u64; 9], DayError> {
     // We store the day state as a vec of timers 0-8, plus 9:Birthed (which reset to 6)
-    let mut fishes = vec![0u64;9];
-    let mut total_fish = 0;
+    let mut fishes = [0u64;9];
     // The input is a comma separated list of timers.
-    for i in from.split(',').map(|c| c.parse::<usize>().unwrap()) {
+    let line = from.trim();
+    for timer in line.split(',') {
+        let i = parse_at::<usize>(timer, 1, line)?;
+        if i > 8 {
+            return Err(DayError::at(1, column_of(line, timer), format!("timer {} is out of range", i)));
+        }
         fishes[i] += 1;
-        total_fish += 1;
     }
+    Ok(fishes)
+}
+
+pub fn evolve(school: &[u64; 9], generations: usize) -> u64 {
+    let mut fishes = *school;
+    let mut total_fish: u64 = fishes.iter().sum();
 
     // For sanity, we take gen 0 as our starting state.
     for _generation in 1..=generations {
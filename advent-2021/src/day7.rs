@@ -1,27 +1,40 @@
 use std::collections::HashMap;
-use std::time::SystemTime;
-use crate::{DayResult, DaySolver};
+use crate::{parse_at, DayError, DayInfo, PartResult, Solution};
 
 pub struct Day {}
 
-impl DaySolver for Day {
-    fn solve(&self) -> DayResult {
-        let data = include_str!("data/day7.dat");
-        let crabs: Vec<i32> = data.split(',').map(|c| c.parse::<i32>().unwrap()).collect();
-        let start = SystemTime::now();
-        let (target, fuel) = least_fuel(&crabs);
-        let (target_crab, fuel_crab) = least_fuel_crabwise(&crabs);
-        let timed = SystemTime::now().duration_since(start).unwrap();
-        let desc1 = format!("The crabs can reach {} with {} fuel.", target, fuel);
+pub const INFO: DayInfo = DayInfo { day: 7, title: "The Treachery of Whales" };
+
+impl Solution for Day {
+    type Parsed = Vec<i32>;
+
+    fn bundled_input(&self) -> &'static str {
+        include_str!("data/day7.dat")
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        crabs(input)
+    }
+
+    fn part1(&self, crabs: &Self::Parsed) -> Result<PartResult, DayError> {
+        let (target, fuel) = least_fuel(crabs);
+        Ok(PartResult::new(fuel, format!("The crabs can reach {} with {} fuel.", target, fuel)))
+    }
+
+    fn part2(&self, crabs: &Self::Parsed) -> Result<PartResult, DayError> {
+        let (target_crab, fuel_crab) = least_fuel_crabwise(crabs);
         let desc2 = format!("In crab mode, the crabs can reach {} with {} fuel.",
                  target_crab, fuel_crab);
-        DayResult{
-            description: format!("{}\n{}", desc1, desc2),
-            part1: format!("{}", fuel),
-            part2: format!("{}", fuel_crab),
-            timing_us: timed.as_micros(),
-        }
+        Ok(PartResult::new(fuel_crab, desc2))
+    }
+}
+
+fn crabs(data: &str) -> Result<Vec<i32>, DayError> {
+    let line = data.trim();
+    if line.is_empty() {
+        return Err(DayError::new("no crab positions"));
     }
+    line.split(',').map(|c| parse_at::<i32>(c, 1, line)).collect()
 }
 
 pub fn least_fuel(crabs: &[i32]) -> (i32, i32) {
@@ -111,7 +124,7 @@ mod tests {
     #[test]
     fn test_data() {
         let data = include_str!("data/test_day7.dat");
-        let crabs: Vec<i32> = data.split(",").map(|c| c.parse::<i32>().unwrap()).collect();
+        let crabs = crabs(data).unwrap();
         let (target, fuel) = least_fuel(&crabs);
         assert_eq!(target, 2);
         assert_eq!(fuel, 37);
@@ -122,6 +135,13 @@ mod tests {
         assert_eq!(fuel_crab, 168);
     }
 
+    #[test]
+    fn test_bad_data() {
+        let err = crabs("16,1,2,0,-").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(1), Some(10)));
+        assert!(crabs("\n").is_err());
+    }
+
     fn cost_to(crabs: &[i32], target: i32) -> i32 {
         let mut fuel = 0;
         for crab in crabs {
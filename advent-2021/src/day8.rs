@@ -1,25 +1,52 @@
 use std::collections::HashMap;
-use std::time::SystemTime;
-use crate::{DayResult, DaySolver};
+use crate::{column_of, DayError, DayInfo, PartResult, Solution};
 
 pub struct Day {}
 
-impl DaySolver for Day {
-    fn solve(&self) -> DayResult {
-        let data = include_str!("data/day8.dat");
-        let start = SystemTime::now();
-        let uniques = count_uniques(data);
-        let decoded_sum = decode(data);
-        let timed = SystemTime::now().duration_since(start).unwrap();
-        let desc1 = format!("Counting 1, 4, 7, 8 -> {}", uniques);
-        let desc2 = format!("Fully decoded sum -> {}", decoded_sum);
-        DayResult{
-            description: format!("{}\n{}", desc1, desc2),
-            part1: format!("{}", uniques),
-            part2: format!("{}", decoded_sum),
-            timing_us: timed.as_micros(),
+pub const INFO: DayInfo = DayInfo { day: 8, title: "Seven Segment Search" };
+
+impl Solution for Day {
+    // The notes, once checked to be well formed.
+    type Parsed = String;
+
+    fn bundled_input(&self) -> &'static str {
+        include_str!("data/day8.dat")
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        check_notes(input)?;
+        Ok(String::from(input))
+    }
+
+    fn part1(&self, notes: &Self::Parsed) -> Result<PartResult, DayError> {
+        let uniques = count_uniques(notes);
+        Ok(PartResult::new(uniques, format!("Counting 1, 4, 7, 8 -> {}", uniques)))
+    }
+
+    fn part2(&self, notes: &Self::Parsed) -> Result<PartResult, DayError> {
+        let decoded_sum = decode(notes)?;
+        Ok(PartResult::new(decoded_sum, format!("Fully decoded sum -> {}", decoded_sum)))
+    }
+}
+
+// Each note should have ten patterns, a '|', then the four output digits.
+fn check_notes(data: &str) -> Result<(), DayError> {
+    for (i, line) in data.lines().enumerate() {
+        let front_back: Vec<&str> = line.split('|').collect();
+        if front_back.len() != 2 {
+            return Err(DayError::at(i + 1, 1, "expected a single '|' separating patterns and output"));
+        }
+        if front_back[0].split_whitespace().count() != 10 {
+            return Err(DayError::at(i + 1, 1, "expected ten signal patterns"));
+        }
+        if front_back[1].split_whitespace().count() != 4 {
+            return Err(DayError::at(i + 1, column_of(line, front_back[1]), "expected four output digits"));
+        }
+        if let Some(col) = line.find(|c: char| !matches!(c, 'a'..='g' | ' ' | '|')) {
+            return Err(DayError::at(i + 1, col + 1, "expected a segment a-g"));
         }
     }
+    Ok(())
 }
 
 fn count_uniques(data: &str) -> i32 {
@@ -40,17 +67,21 @@ fn count_uniques(data: &str) -> i32 {
 
 }
 
-fn decode(data: &str) -> usize {
-    data.lines().map(|l| decode_line(l)).sum()
+fn decode(data: &str) -> Result<usize, DayError> {
+    let mut sum = 0;
+    for (i, line) in data.lines().enumerate() {
+        sum += decode_line(line).map_err(|e| e.on_line(i + 1))?;
+    }
+    Ok(sum)
 }
 
-fn decode_line(data: &str) -> usize {
+fn decode_line(data: &str) -> Result<usize, DayError> {
     // Get the front and back parts.
     let front_back: Vec<&str> = data.split('|').collect();
     let front: Vec<&str> = front_back[0].split_whitespace().collect();
     let back: Vec<&str> = front_back[1].split_whitespace().collect();
 
-    let decoder = decode_numbers(front);
+    let decoder = decode_numbers(front)?;
 
     // Now we have the decoder we can work out the represented digit.
     let mut result = 0;
@@ -66,10 +97,10 @@ fn decode_line(data: &str) -> usize {
         power /= 10;
     }
 
-    result
+    Ok(result)
 }
 
-fn decode_numbers(front: Vec<&str>) -> Vec<Representation> {
+fn decode_numbers(front: Vec<&str>) -> Result<Vec<Representation>, DayError> {
     let mut numbers = vec![Representation::from(""); 10];
     let mut segments = vec![Representation::from(""); 7];
 
@@ -86,6 +117,10 @@ fn decode_numbers(front: Vec<&str>) -> Vec<Representation> {
         }
     }
 
+    if maybe235.len() != 3 {
+        return Err(DayError::new(format!("expected three five segment patterns, found {}", maybe235.len())));
+    }
+
     segments[0] = numbers[7].minus(&numbers[1]);
 
     // We can work out 3, as it differs in only one place from 2 and 5 (which differ by two from each other).
@@ -103,7 +138,10 @@ fn decode_numbers(front: Vec<&str>) -> Vec<Representation> {
     segments[1] = numbers[4].minus(&numbers[1]).minus(&segments[3]);
 
     // Can now work out which is 2 and which is 5.
-    let s1 = segments[1].segments.keys().collect::<Vec<&char>>()[0];
+    let s1 = match segments[1].segments.keys().next() {
+        Some(s1) => s1,
+        None => return Err(DayError::new("patterns are inconsistent")),
+    };
     for maybe in maybe235 {
         if maybe.segments.contains_key(s1) {
             // This is the 5
@@ -120,7 +158,7 @@ fn decode_numbers(front: Vec<&str>) -> Vec<Representation> {
     numbers[6] = numbers[5].add(&segments[4]);
     numbers[9] = numbers[8].minus(&segments[4]);
 
-    numbers
+    Ok(numbers)
 }
 
 #[derive(Clone)]
@@ -198,7 +236,7 @@ mod tests {
     fn test_short_data() {
         let data = include_str!("data/test_short8.dat");
         let uniques = count_uniques(data);
-        let decoded_sum = decode(data);
+        let decoded_sum = decode(data).unwrap();
         assert_eq!(uniques, 0);
         assert_eq!(decoded_sum, 5353);
     }
@@ -207,8 +245,18 @@ mod tests {
     fn test_data() {
         let data = include_str!("data/test_day8.dat");
         let uniques = count_uniques(data);
-        let decoded_sum = decode(data);
+        let decoded_sum = decode(data).unwrap();
         assert_eq!(uniques, 26);
         assert_eq!(decoded_sum, 61229);
     }
+
+    #[test]
+    fn test_bad_data() {
+        let data = include_str!("data/test_short8.dat");
+        assert!(check_notes(data).is_ok());
+        let err = check_notes("acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab cdfeb fcadb cdfeb cdbaf").unwrap_err();
+        assert_eq!(err.line, Some(1));
+        let err = check_notes("acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbax").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(1), Some(84)));
+    }
 }
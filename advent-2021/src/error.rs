@@ -0,0 +1,135 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+// Why a day failed, and where in its input if that's known.
+// Lines and columns count from 1. The day is filled in by the runner.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DayError {
+    pub day: Option<usize>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub reason: String,
+    // Whether the day was stopped for running past its time limit, rather than failing.
+    pub timed_out: bool,
+}
+
+impl DayError {
+    pub fn new<S: Into<String>>(reason: S) -> Self {
+        Self {
+            day: None,
+            line: None,
+            column: None,
+            reason: reason.into(),
+            timed_out: false,
+        }
+    }
+
+    // The column can be None where it couldn't be worked out, see column_of.
+    pub fn at<C: Into<Option<usize>>, S: Into<String>>(line: usize, column: C, reason: S) -> Self {
+        Self {
+            day: None,
+            line: Some(line),
+            column: column.into(),
+            reason: reason.into(),
+            timed_out: false,
+        }
+    }
+
+    pub fn timed_out(limit: Duration) -> Self {
+        Self {
+            timed_out: true,
+            ..Self::new(format!("timed out after {}ms", limit.as_millis()))
+        }
+    }
+
+    pub fn on_line(mut self, line: usize) -> Self {
+        self.line = Some(line);
+        self
+    }
+
+    pub fn at_column<C: Into<Option<usize>>>(mut self, column: C) -> Self {
+        self.column = column.into();
+        self
+    }
+
+    pub fn for_day(mut self, day: usize) -> Self {
+        self.day = Some(day);
+        self
+    }
+}
+
+impl fmt::Display for DayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(day) = self.day {
+            write!(f, "Day {}", day)?;
+        } else {
+            write!(f, "Input")?;
+        }
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, " (line {}, column {})", line, column)?,
+            (Some(line), None) => write!(f, " (line {})", line)?,
+            _ => (),
+        }
+        write!(f, ": {}", self.reason)
+    }
+}
+
+impl std::error::Error for DayError {}
+
+// The 1-based column at which `token` starts, counting characters as Grid::parse does. None
+// unless the token is a slice of `line`.
+pub(crate) fn column_of(line: &str, token: &str) -> Option<usize> {
+    offset_of(line, token).map(|offset| line[..offset].chars().count() + 1)
+}
+
+// The byte offset of `token` within `line`, or None if it isn't a slice of it.
+pub(crate) fn offset_of(line: &str, token: &str) -> Option<usize> {
+    let offset = (token.as_ptr() as usize).checked_sub(line.as_ptr() as usize)?;
+    Some(offset).filter(|&offset| offset + token.len() <= line.len())
+}
+
+// Parse a value from `token`, which sits somewhere in the given line of input. Only the day
+// modules use this, so it goes unused in a build without any of them.
+#[cfg_attr(not(feature = "all-days"), allow(dead_code))]
+pub(crate) fn parse_at<T: FromStr>(token: &str, line_no: usize, line: &str) -> Result<T, DayError> {
+    token.parse::<T>().map_err(|_| {
+        DayError::at(
+            line_no,
+            column_of(line, token),
+            format!("could not parse '{}'", token),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_at() {
+        let line = "12,x4,7";
+        let tokens = line.split(',').collect::<Vec<&str>>();
+        assert_eq!(parse_at::<i32>(tokens[2], 3, line), Ok(7));
+
+        let err = parse_at::<i32>(tokens[1], 3, line).unwrap_err().for_day(5);
+        assert_eq!((err.line, err.column), (Some(3), Some(4)));
+        assert_eq!(
+            err.to_string(),
+            "Day 5 (line 3, column 4): could not parse 'x4'"
+        );
+        assert_eq!(DayError::new("no input").to_string(), "Input: no input");
+    }
+
+    #[test]
+    fn test_column_of() {
+        let line = "é→x,y";
+        assert_eq!(column_of(line, &line[5..6]), Some(3));
+        assert_eq!(column_of(line, &line[line.len()..]), Some(6));
+        // A copy of a token is somewhere else.
+        let copy = String::from("x");
+        assert_eq!(column_of(line, &copy), None);
+        assert_eq!(column_of(&line[2..], line), None);
+        assert_eq!(DayError::at(2, column_of(line, &copy), "bad").to_string(), "Input (line 2): bad");
+    }
+}
@@ -0,0 +1,171 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+// Where downloaded inputs are kept, as <year>/dayN.dat, relative to where the runner is started.
+pub const DEFAULT_CACHE_DIR: &str = "inputs";
+
+// The file the session token is read from if AOC_SESSION isn't set.
+pub const DEFAULT_SESSION_FILE: &str = ".aoc-session";
+
+// The site asks that automated requests say where they come from.
+const USER_AGENT: &str = concat!("advent-2021/", env!("CARGO_PKG_VERSION"), " (puzzle input fetch, cached)");
+
+// Downloads puzzle inputs, each at most once: an input in the cache is never asked for again.
+pub struct Fetcher {
+    base_url: String,
+    cache_dir: PathBuf,
+    // Only needed to download, so can be None if everything wanted is cached.
+    session: Option<String>,
+}
+
+// Whether an input was downloaded or already there.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Fetched {
+    Downloaded,
+    Cached,
+}
+
+impl Fetcher {
+    pub fn new(base_url: &str, cache_dir: &Path, session: Option<String>) -> Self {
+        Self {
+            base_url: String::from(base_url.trim_end_matches('/')),
+            cache_dir: cache_dir.to_path_buf(),
+            session,
+        }
+    }
+
+    pub fn cached_path(&self, year: usize, day: usize) -> PathBuf {
+        self.cache_dir.join(year.to_string()).join(format!("day{}.dat", day))
+    }
+
+    // The cached input for a day, downloading it first if it isn't there.
+    pub fn fetch(&self, year: usize, day: usize) -> Result<(PathBuf, Fetched), String> {
+        let path = self.cached_path(year, day);
+        if path.is_file() {
+            return Ok((path, Fetched::Cached));
+        }
+        let input = self.download(year, day)?;
+        let parent = path.parent().expect("cached inputs are in a year directory");
+        fs::create_dir_all(parent).map_err(|e| format!("{}: {}", parent.display(), e))?;
+        // Written to one side first, so an interrupted write is never taken for the input.
+        let partial = path.with_extension("dat.part");
+        fs::write(&partial, input)
+            .and_then(|()| fs::rename(&partial, &path))
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+        Ok((path, Fetched::Downloaded))
+    }
+
+    fn download(&self, year: usize, day: usize) -> Result<String, String> {
+        let session = self.session.as_deref().ok_or_else(|| {
+            format!(
+                "No session token to download day {} with, set AOC_SESSION or save it to a session file, {} by default",
+                day, DEFAULT_SESSION_FILE
+            )
+        })?;
+        let url = format!("{}/{}/day/{}/input", self.base_url, year, day);
+        let agent = ureq::AgentBuilder::new()
+            .timeout(Duration::from_secs(30))
+            .user_agent(USER_AGENT)
+            .build();
+        match agent.get(&url).set("Cookie", &format!("session={}", session)).call() {
+            Ok(response) => response.into_string().map_err(|e| format!("{}: {}", url, e)),
+            Err(ureq::Error::Status(404, _)) => Err(format!("Day {} of {} isn't unlocked yet", day, year)),
+            // Expired tokens get redirected to a page asking for a login, or refused outright.
+            Err(ureq::Error::Status(code @ (400 | 401 | 403), _)) => {
+                Err(format!("{}: {}, the session token may have expired", url, code))
+            }
+            Err(ureq::Error::Status(code, _)) => Err(format!("{}: {}", url, code)),
+            Err(e) => Err(format!("{}: {}", url, e)),
+        }
+    }
+}
+
+// The session cookie from the site, from AOC_SESSION or else the contents of `file`. None if
+// neither is set.
+pub fn session_token(file: &Path) -> Option<String> {
+    env::var("AOC_SESSION")
+        .ok()
+        .or_else(|| fs::read_to_string(file).ok())
+        .map(|token| token.trim().to_string())
+        .filter(|token| !token.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    // Serves `/2021/day/1/input` to requests carrying the session cookie, refuses it to any others,
+    // and gives 404 for every other day, recording the path of each request. Returns its base URL.
+    fn stub_server(requests: Arc<Mutex<Vec<String>>>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request = String::new();
+                reader.read_line(&mut request).unwrap();
+                let mut cookie = String::new();
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    if header.trim().is_empty() {
+                        break;
+                    }
+                    if let Some(value) = header.strip_prefix("Cookie: ") {
+                        cookie = value.trim().to_string();
+                    }
+                }
+                let path = request.split_whitespace().nth(1).unwrap_or_default().to_string();
+                let (status, body) = match (path.as_str(), cookie.as_str()) {
+                    ("/2021/day/1/input", "session=secret") => ("200 OK", "199\n200\n208\n"),
+                    ("/2021/day/1/input", _) => ("400 Bad Request", "Please log in"),
+                    _ => ("404 Not Found", "Not found"),
+                };
+                requests.lock().unwrap().push(path);
+                let response = format!(
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        base_url
+    }
+
+    #[test]
+    fn test_fetch() {
+        let requests = Arc::new(Mutex::new(vec![]));
+        let base_url = stub_server(Arc::clone(&requests));
+        let cache = env::temp_dir().join(format!("advent21-fetch-{}", std::process::id()));
+
+        let fetcher = Fetcher::new(&format!("{}/", base_url), &cache, Some(String::from("secret")));
+        let (path, fetched) = fetcher.fetch(2021, 1).unwrap();
+        assert_eq!((path.clone(), fetched), (cache.join("2021").join("day1.dat"), Fetched::Downloaded));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "199\n200\n208\n");
+
+        // Once cached, an input is never asked for again, even without a token.
+        assert_eq!(fetcher.fetch(2021, 1).unwrap().1, Fetched::Cached);
+        assert_eq!(Fetcher::new(&base_url, &cache, None).fetch(2021, 1).unwrap().1, Fetched::Cached);
+        assert_eq!(*requests.lock().unwrap(), vec!["/2021/day/1/input"]);
+
+        assert_eq!(fetcher.fetch(2021, 2).unwrap_err(), "Day 2 of 2021 isn't unlocked yet");
+        assert!(!fetcher.cached_path(2021, 2).exists());
+        assert!(Fetcher::new(&base_url, &cache, None).fetch(2021, 3).unwrap_err().starts_with("No session token"));
+        let expired = Fetcher::new(&base_url, &env::temp_dir().join("advent21-fetch-none"), Some(String::from("old")));
+        assert!(expired.fetch(2021, 1).unwrap_err().ends_with("400, the session token may have expired"));
+        assert_eq!(requests.lock().unwrap().len(), 3);
+
+        fs::remove_dir_all(cache).unwrap();
+    }
+}
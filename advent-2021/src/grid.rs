@@ -0,0 +1,253 @@
+use crate::DayError;
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+// A position in a grid, as (row, column) from the top left.
+pub type Pos = (usize, usize);
+
+// Offsets to the cells sharing an edge.
+pub const ORTHOGONAL: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+
+// Offsets to every cell around, diagonals included, in reading order.
+pub const SURROUNDING: [(isize, isize); 8] = [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)];
+
+// A rectangular grid, kept row by row in a single Vec.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+    // Whether stepping off one edge comes back on the opposite one.
+    wraps: bool,
+}
+
+impl<T> Grid<T> {
+    pub fn from_fn<F: FnMut(Pos) -> T>(width: usize, height: usize, mut cell: F) -> Self {
+        let cells = (0..height).flat_map(|i| (0..width).map(move |j| (i, j))).map(&mut cell).collect();
+        Self {
+            width,
+            height,
+            cells,
+            wraps: false,
+        }
+    }
+
+    // Read a map with one character per cell. `cell` turns each character into a value, or
+    // explains what was wrong with it. Errors are positioned within `input`.
+    pub fn parse<F>(input: &str, mut cell: F) -> Result<Self, DayError>
+    where
+        F: FnMut(char) -> Result<T, String>,
+    {
+        let mut cells = vec![];
+        let mut width = 0;
+        let mut height = 0;
+        for line in crate::lines(input) {
+            let start = cells.len();
+            for (j, c) in line.text.chars().enumerate() {
+                cells.push(cell(c).map_err(|reason| DayError::at(line.no, j + 1, reason))?);
+            }
+            let row_width = cells.len() - start;
+            if line.no == 1 {
+                width = row_width;
+            } else if row_width != width {
+                return Err(DayError::at(line.no, 1, format!("expected a row of {}, found {}", width, row_width)));
+            }
+            height += 1;
+        }
+        if cells.is_empty() {
+            return Err(DayError::new("the map is empty"));
+        }
+        Ok(Self {
+            width,
+            height,
+            cells,
+            wraps: false,
+        })
+    }
+
+    // Have the neighbours of cells on an edge include those on the opposite edge.
+    pub fn wrapping(mut self) -> Self {
+        self.wraps = true;
+        self
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.offset(pos).map(|at| &self.cells[at])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        self.offset(pos).map(move |at| &mut self.cells[at])
+    }
+
+    // Every position, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |i| (0..width).map(move |j| (i, j)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut()
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+            wraps: self.wraps,
+        }
+    }
+
+    // The position `offset` away from `pos`, if it's on the grid (or wraps round onto it).
+    pub fn step(&self, pos: Pos, offset: (isize, isize)) -> Option<Pos> {
+        let along = |at: usize, by: isize, size: usize| {
+            let moved = at as isize + by;
+            if self.wraps {
+                Some(moved.rem_euclid(size as isize) as usize)
+            } else if (0..size as isize).contains(&moved) {
+                Some(moved as usize)
+            } else {
+                None
+            }
+        };
+        Some((along(pos.0, offset.0, self.height)?, along(pos.1, offset.1, self.width)?))
+    }
+
+    // The cells sharing an edge with `pos`.
+    pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        ORTHOGONAL.iter().filter_map(move |&offset| self.step(pos, offset))
+    }
+
+    // The cells around `pos`, diagonals included.
+    pub fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        SURROUNDING.iter().filter_map(move |&offset| self.step(pos, offset))
+    }
+
+    fn offset(&self, (i, j): Pos) -> Option<usize> {
+        if i < self.height && j < self.width {
+            Some(i * self.width + j)
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+            wraps: false,
+        }
+    }
+}
+
+impl Grid<u8> {
+    // Read a map of single digits.
+    pub fn parse_digits(input: &str) -> Result<Self, DayError> {
+        Self::parse(input, |c| {
+            c.to_digit(10)
+                .map(|d| d as u8)
+                .ok_or_else(|| format!("expected a digit, found '{}'", c))
+        })
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        match self.offset(pos) {
+            Some(at) => &self.cells[at],
+            None => panic!("{:?} is outside a {}x{} grid", pos, self.width, self.height),
+        }
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        match self.offset(pos) {
+            Some(at) => &mut self.cells[at],
+            None => panic!("{:?} is outside a {}x{} grid", pos, self.width, self.height),
+        }
+    }
+}
+
+// Each row on its own line, with the cells written one after another.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, row) in self.rows().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let grid = Grid::parse_digits("123\n456").unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 0)], 4);
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.to_string(), "123\n456");
+
+        let err = Grid::parse_digits("123\n4x6").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(2), Some(2)));
+        assert_eq!(Grid::parse_digits("123\n45").unwrap_err().line, Some(2));
+        assert!(Grid::parse_digits("").is_err());
+        assert_eq!(Grid::parse_digits("123\n456\n\n").unwrap(), grid);
+
+        let chars = Grid::parse("#.\n.#", |c| Ok(c == '#')).unwrap();
+        assert_eq!(chars.map(|&lit| if lit { 'X' } else { ' ' }).to_string(), "X \n X");
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = Grid::new(3, 3, 0);
+        assert_eq!(grid.neighbours4((0, 0)).collect::<Vec<Pos>>(), vec![(0, 1), (1, 0)]);
+        assert_eq!(grid.neighbours4((1, 1)).count(), 4);
+        assert_eq!(grid.neighbours8((1, 1)).count(), 8);
+        assert_eq!(grid.neighbours8((2, 2)).collect::<Vec<Pos>>(), vec![(1, 1), (1, 2), (2, 1)]);
+
+        let wrapping = grid.wrapping();
+        assert_eq!(
+            wrapping.neighbours4((0, 0)).collect::<Vec<Pos>>(),
+            vec![(2, 0), (0, 2), (0, 1), (1, 0)]
+        );
+        assert_eq!(wrapping.neighbours8((0, 0)).count(), 8);
+    }
+
+    #[test]
+    fn test_positions() {
+        let grid = Grid::from_fn(2, 2, |(i, j)| i * 10 + j);
+        assert_eq!(grid.positions().collect::<Vec<Pos>>(), vec![(0, 0), (0, 1), (1, 0), (1, 1)]);
+        assert_eq!(grid.iter().copied().collect::<Vec<usize>>(), vec![0, 1, 10, 11]);
+        assert_eq!(grid.rows().nth(1), Some(&[10, 11][..]));
+    }
+}
@@ -0,0 +1,348 @@
+use crate::{crate_dir, pretty_duration, DayResult, Timings};
+use std::env;
+use std::fmt;
+use std::fs;
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Where runs are recorded and compared, relative to where the runner is started.
+pub const DEFAULT_HISTORY: &str = "history.csv";
+
+// The first line of a history file, naming the fields of each record.
+pub const HISTORY_HEADER: &str = "revision,machine,recorded_ms,year,day,parse_us,part1_us,part2_us,total_us";
+
+// How much slower a day has to get to be flagged, as a percentage, if not given.
+pub const DEFAULT_THRESHOLD: usize = 10;
+
+// Days that got slower by less than this are within the timing noise, whatever the percentage.
+const NOISE_US: u128 = 100;
+
+// What compare checks the latest run against.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompareConfig {
+    // The revision, or the start of one, to compare with. None compares with the run before.
+    pub baseline: Option<String>,
+    pub threshold: usize,
+}
+
+impl Default for CompareConfig {
+    fn default() -> Self {
+        Self {
+            baseline: None,
+            threshold: DEFAULT_THRESHOLD,
+        }
+    }
+}
+
+// The timings of one day in one run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub revision: String,
+    pub machine: String,
+    // When the run started, in milliseconds since the epoch. Together with the revision and
+    // machine this tells runs apart.
+    pub recorded_ms: u128,
+    pub year: usize,
+    pub day: usize,
+    pub timings: Timings,
+}
+
+impl Record {
+    pub fn new(run: &Run, year: usize, day: usize, result: &DayResult) -> Self {
+        Self {
+            revision: run.revision.clone(),
+            machine: run.machine.clone(),
+            recorded_ms: run.recorded_ms,
+            year,
+            day,
+            timings: result.timings,
+        }
+    }
+
+    fn parse(line: &str) -> Option<Self> {
+        let fields = line.split(',').collect::<Vec<&str>>();
+        match fields[..] {
+            [revision, machine, recorded_ms, year, day, parse_us, part1_us, part2_us, _total_us] => Some(Self {
+                revision: String::from(revision),
+                machine: String::from(machine),
+                recorded_ms: recorded_ms.parse().ok()?,
+                year: year.parse().ok()?,
+                day: day.parse().ok()?,
+                timings: Timings {
+                    parse_us: parse_us.parse().ok()?,
+                    part1_us: part1_us.parse().ok()?,
+                    part2_us: part2_us.parse().ok()?,
+                },
+            }),
+            _ => None,
+        }
+    }
+
+    fn run(&self) -> Run {
+        Run {
+            revision: self.revision.clone(),
+            machine: self.machine.clone(),
+            recorded_ms: self.recorded_ms,
+        }
+    }
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let t = &self.timings;
+        write!(
+            f,
+            "{},{},{},{},{},{},{},{},{}",
+            self.revision,
+            self.machine,
+            self.recorded_ms,
+            self.year,
+            self.day,
+            t.parse_us,
+            t.part1_us,
+            t.part2_us,
+            t.total_us()
+        )
+    }
+}
+
+// One invocation of the runner: what was built, where, and when.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Run {
+    pub revision: String,
+    pub machine: String,
+    pub recorded_ms: u128,
+}
+
+impl Run {
+    // A run starting now, of the checked out revision. `machine` overrides the machine label.
+    pub fn start(machine: Option<&str>) -> Self {
+        Self {
+            revision: git_revision(),
+            machine: machine.map(String::from).unwrap_or_else(machine_label),
+            recorded_ms: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis()),
+        }
+    }
+}
+
+impl fmt::Display for Run {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} on {}", self.revision, self.machine)
+    }
+}
+
+// Every run recorded in a history file, oldest first.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct History {
+    pub records: Vec<Record>,
+}
+
+impl History {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut records = vec![];
+        for (i, line) in text.lines().enumerate() {
+            if line.is_empty() || line == HISTORY_HEADER {
+                continue;
+            }
+            records.push(Record::parse(line).ok_or_else(|| format!("line {}: expected {}", i + 1, HISTORY_HEADER))?);
+        }
+        Ok(Self { records })
+    }
+
+    // The runs made on `machine`, in the order they were recorded.
+    pub fn runs(&self, machine: &str) -> Vec<Run> {
+        let mut runs: Vec<Run> = vec![];
+        for record in self.records.iter().filter(|r| r.machine == machine) {
+            let run = record.run();
+            if !runs.contains(&run) {
+                runs.push(run);
+            }
+        }
+        runs
+    }
+
+    pub fn records<'a>(&'a self, run: &'a Run) -> impl Iterator<Item = &'a Record> {
+        self.records.iter().filter(move |r| r.run() == *run)
+    }
+
+    // The latest run on `machine`, and the one to compare it with: the latest before it at the
+    // baseline revision if there is one, otherwise the run before.
+    pub fn latest_pair(&self, machine: &str, baseline: Option<&str>) -> Result<(Run, Run), String> {
+        let mut runs = self.runs(machine);
+        let latest = runs.pop().ok_or_else(|| format!("No runs have been recorded on {}", machine))?;
+        let before = match baseline {
+            Some(baseline) => runs
+                .into_iter()
+                .rev()
+                .find(|r| r.revision.starts_with(baseline))
+                .ok_or_else(|| format!("No earlier run of {} has been recorded on {}", baseline, machine))?,
+            None => runs
+                .pop()
+                .ok_or_else(|| format!("Only one run has been recorded on {}", machine))?,
+        };
+        Ok((before, latest))
+    }
+
+    // How each day solved in both runs changed.
+    pub fn compare(&self, before: &Run, after: &Run) -> Vec<Change> {
+        self.records(after)
+            .filter_map(|now| {
+                let then = self.records(before).find(|r| (r.year, r.day) == (now.year, now.day))?;
+                Some(Change {
+                    year: now.year,
+                    day: now.day,
+                    before_us: then.timings.total_us(),
+                    after_us: now.timings.total_us(),
+                })
+            })
+            .collect()
+    }
+}
+
+// How the time taken by a day changed between two runs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Change {
+    pub year: usize,
+    pub day: usize,
+    pub before_us: u128,
+    pub after_us: u128,
+}
+
+impl Change {
+    // The change in time taken, as a percentage of the time before.
+    pub fn percent(&self) -> f64 {
+        if self.before_us == 0 {
+            return 0.0;
+        }
+        (self.after_us as f64 - self.before_us as f64) * 100.0 / self.before_us as f64
+    }
+
+    pub fn regressed(&self, threshold: usize) -> bool {
+        self.after_us >= self.before_us + NOISE_US && self.percent() > threshold as f64
+    }
+}
+
+pub const COMPARE_HEADER: &str = "Year  Day      Before       After   Change";
+
+pub fn compare_row(change: &Change, threshold: usize) -> String {
+    let mut row = format!(
+        "{:>4}  {:>3}  {:>10}  {:>10}  {:>+6.1}%",
+        change.year,
+        change.day,
+        pretty_duration(change.before_us as f64),
+        pretty_duration(change.after_us as f64),
+        change.percent()
+    );
+    if change.regressed(threshold) {
+        row.push_str("  SLOWER");
+    }
+    row
+}
+
+// The short hash of the checked out commit, marked if there are changes that aren't committed,
+// or "unknown" outside a git checkout.
+pub fn git_revision() -> String {
+    let git = |args: &[&str]| {
+        process::Command::new("git")
+            .args(args)
+            .current_dir(crate_dir())
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+    match git(&["rev-parse", "--short", "HEAD"]) {
+        Some(revision) if git(&["status", "--porcelain", "--untracked-files=no"]).is_some_and(|s| s.is_empty()) => {
+            revision
+        }
+        Some(revision) => format!("{}+dirty", revision),
+        None => String::from("unknown"),
+    }
+}
+
+// Which machine timings come from, as only timings from the same machine are worth comparing.
+// Set by ADVENT_MACHINE, or the host name by default.
+pub fn machine_label() -> String {
+    let label = env::var("ADVENT_MACHINE")
+        .ok()
+        .or_else(|| env::var("HOSTNAME").ok())
+        .or_else(|| fs::read_to_string("/etc/hostname").ok())
+        .map(|label| label.trim().to_string())
+        .filter(|label| !label.is_empty())
+        .unwrap_or_else(|| String::from("unknown"));
+    // The label is a field of each record.
+    label.replace([',', '\n', '\r'], "_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(revision: &str, recorded_ms: u128, day: usize, part1_us: u128) -> Record {
+        Record {
+            revision: String::from(revision),
+            machine: String::from("laptop"),
+            recorded_ms,
+            year: 2021,
+            day,
+            timings: Timings {
+                parse_us: 10,
+                part1_us,
+                part2_us: 0,
+            },
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let line = "abc123,laptop,1634000000000,2021,19,10,2000,3000,5010";
+        let history = History::parse(&format!("{}\n{}\n", HISTORY_HEADER, line)).unwrap();
+        assert_eq!(history.records.len(), 1);
+        assert_eq!(history.records[0].day, 19);
+        assert_eq!(history.records[0].to_string(), line);
+        assert_eq!(History::parse("a,b,c").unwrap_err(), format!("line 1: expected {}", HISTORY_HEADER));
+    }
+
+    #[test]
+    fn test_compare() {
+        let history = History {
+            records: vec![
+                record("aaa", 1, 19, 1000),
+                record("aaa", 1, 23, 1000),
+                record("bbb", 2, 19, 1000),
+                record("bbb", 2, 23, 2000),
+                record("ccc", 3, 19, 1500),
+                record("ccc", 3, 23, 2050),
+                record("ccc", 3, 6, 1),
+            ],
+        };
+        assert_eq!(history.runs("laptop").len(), 3);
+        assert!(history.runs("desktop").is_empty());
+
+        let (before, after) = history.latest_pair("laptop", None).unwrap();
+        assert_eq!((before.revision.as_str(), after.revision.as_str()), ("bbb", "ccc"));
+        let changes = history.compare(&before, &after);
+        assert_eq!(changes.len(), 2);
+        assert!(changes[0].regressed(10));
+        assert!(!changes[0].regressed(50));
+        // 2.5% slower isn't enough.
+        assert!(!changes[1].regressed(DEFAULT_THRESHOLD));
+
+        let (before, _) = history.latest_pair("laptop", Some("a")).unwrap();
+        assert_eq!(before.revision, "aaa");
+        let changes = history.compare(&before, &after);
+        assert!(changes.iter().all(|c| c.regressed(DEFAULT_THRESHOLD)));
+        assert!(history.latest_pair("laptop", Some("zzz")).is_err());
+        assert!(history.latest_pair("desktop", None).is_err());
+
+        let noise = Change {
+            year: 2021,
+            day: 6,
+            before_us: 10,
+            after_us: 50,
+        };
+        assert_eq!(noise.percent(), 400.0);
+        assert!(!noise.regressed(DEFAULT_THRESHOLD));
+        assert_eq!(compare_row(&changes[1], DEFAULT_THRESHOLD), "2021   23      1.01ms      2.06ms  +104.0%  SLOWER");
+    }
+}
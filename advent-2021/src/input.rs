@@ -0,0 +1,84 @@
+use crate::DaySolver;
+use std::fs;
+use std::io::{self, Read};
+use std::path::PathBuf;
+
+// Where the puzzle input for a day is read from.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum InputSource {
+    // The input bundled in src/data at build time.
+    #[default]
+    Bundled,
+    // A single input file.
+    File(PathBuf),
+    // A directory of dayN.dat files. Days without a file fall back to the bundled input.
+    Dir(PathBuf),
+    // Read the whole input from stdin.
+    Stdin,
+}
+
+pub fn load_input(day: usize, solver: &dyn DaySolver, source: &InputSource) -> io::Result<String> {
+    match source {
+        InputSource::Bundled => Ok(String::from(solver.bundled_input())),
+        InputSource::File(path) => fs::read_to_string(path),
+        InputSource::Dir(dir) => {
+            let path = dir.join(format!("day{}.dat", day));
+            if path.is_file() {
+                fs::read_to_string(path)
+            } else {
+                Ok(String::from(solver.bundled_input()))
+            }
+        }
+        InputSource::Stdin => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+            Ok(input)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DayError, PartResult, Solution};
+
+    struct Echo {}
+
+    impl Solution for Echo {
+        type Parsed = String;
+
+        fn bundled_input(&self) -> &'static str {
+            "bundled"
+        }
+
+        fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+            Ok(String::from(input))
+        }
+
+        fn part1(&self, input: &Self::Parsed) -> Result<PartResult, DayError> {
+            Ok(PartResult::new(input.as_str(), ""))
+        }
+
+        fn part2(&self, _: &Self::Parsed) -> Result<PartResult, DayError> {
+            Ok(PartResult::new("", ""))
+        }
+    }
+
+    #[test]
+    fn test_dir_fallback() {
+        let dir = std::env::temp_dir().join(format!("advent21-input-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("day2.dat"), "from file").unwrap();
+
+        let source = InputSource::Dir(dir.clone());
+        assert_eq!(load_input(2, &Echo {}, &source).unwrap(), "from file");
+        assert_eq!(load_input(3, &Echo {}, &source).unwrap(), "bundled");
+        assert_eq!(
+            load_input(2, &Echo {}, &InputSource::File(dir.join("day2.dat"))).unwrap(),
+            "from file"
+        );
+        assert!(load_input(2, &Echo {}, &InputSource::File(dir.join("missing.dat"))).is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
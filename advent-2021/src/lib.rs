@@ -1,61 +1,408 @@
-mod day1;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day2;
-mod day20;
-mod day21;
-mod day22;
-mod day23;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
+mod answer;
+mod batch;
+mod bench;
+#[cfg(feature = "color")]
+mod color;
+mod error;
+#[cfg(feature = "fetch")]
+mod fetch;
+mod grid;
+mod history;
+mod input;
+mod manifest;
+mod memory;
+mod options;
+mod output;
+mod parallel;
+mod parse;
+#[cfg(feature = "color")]
+mod play;
+mod registry;
+mod report;
+mod rerun;
+mod scaffold;
+mod trace;
+#[cfg(feature = "color")]
+mod tui;
+mod watch;
+
+pub use answer::Answer;
+pub use batch::{batch_inputs, batch_table, solve_batch, BatchInput, BatchResult};
+pub use bench::{bench, bench_summary, pretty_duration, BenchConfig, BenchResult, Stats};
+#[cfg(feature = "color")]
+pub use color::{brighten, fmt_bright, print_day};
+pub use error::DayError;
+#[cfg(feature = "fetch")]
+pub use fetch::{session_token, Fetched, Fetcher, DEFAULT_BASE_URL, DEFAULT_CACHE_DIR, DEFAULT_SESSION_FILE};
+pub use grid::{Grid, Pos, ORTHOGONAL, SURROUNDING};
+pub(crate) use error::{column_of, offset_of};
+#[cfg_attr(not(feature = "all-days"), allow(unused_imports))]
+pub(crate) use error::parse_at;
+pub use history::{
+    compare_row, git_revision, machine_label, Change, CompareConfig, History, Record, Run, COMPARE_HEADER, DEFAULT_HISTORY,
+    DEFAULT_THRESHOLD, HISTORY_HEADER,
+};
+pub use input::{load_input, InputSource};
+pub use manifest::{verify, verify_row, Answers, Expected, Manifest, Verification, DEFAULT_MANIFEST, VERIFY_HEADER};
+pub use memory::{pretty_bytes, Memory};
+#[cfg(feature = "alloc-stats")]
+pub use memory::Counting;
+pub use options::{Command, FetchConfig, Options};
+pub use output::{csv_record, json_record, Format, CSV_HEADER};
+pub use parallel::{run_parallel, worker_count};
+pub use parse::{lines, sections, KeyRange, Line, Section};
+#[cfg(feature = "color")]
+pub use play::play;
+pub use registry::{DayInfo, Entry, Registered, Registry, Year, DAYS_PER_YEAR};
+pub use report::{update_section, Report, ReportDay, REPORT_END, REPORT_START};
+pub use rerun::{rerun_source, watched_input, Poller, POLL_INTERVAL};
+pub use scaffold::{crate_dir, scaffold_day};
+pub use trace::{trace_line, tracing, Level, Tracing};
+#[cfg(feature = "color")]
+pub use tui::run_tui;
+pub use watch::{checkpoint, run_day_limited, run_day_watched, start_day, CancelToken, Progress, Running, Watch};
+
+use parse::trim_blank_lines;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::Instant;
+
+// Declares the day modules and lists what each registers: its INFO, which says which day it
+// is, and its solver. Days are keyed by that number, so the order here doesn't matter. A day
+// whose feature is turned off isn't compiled, and is listed by compiled_out() instead.
+macro_rules! register_days {
+    ($(#[cfg($feature:meta)] $module:ident),* $(,)?) => {
+        $(#[cfg($feature)] mod $module;)*
+
+        #[allow(unused_mut, clippy::vec_init_then_push)]
+        fn registrations() -> Vec<(&'static str, Registered)> {
+            let mut registrations = vec![];
+            $(
+                #[cfg($feature)]
+                registrations.push((
+                    stringify!($module),
+                    Registered {
+                        info: $module::INFO,
+                        solver: &$module::Day {},
+                    },
+                ));
+            )*
+            registrations
+        }
+
+        // Without the module there's no INFO, so the day comes from the module name.
+        #[allow(unused_mut, clippy::vec_init_then_push)]
+        fn compiled_out() -> Vec<usize> {
+            let mut days: Vec<usize> = vec![];
+            $(
+                #[cfg(not($feature))]
+                days.push(stringify!($module)["day".len()..].parse().unwrap());
+            )*
+            days
+        }
+    };
+}
+
+register_days! {
+    #[cfg(feature = "day1")] day1,
+    #[cfg(feature = "day2")] day2,
+    #[cfg(feature = "day3")] day3,
+    #[cfg(feature = "day4")] day4,
+    #[cfg(feature = "day5")] day5,
+    #[cfg(feature = "day6")] day6,
+    #[cfg(feature = "day7")] day7,
+    #[cfg(feature = "day8")] day8,
+    #[cfg(feature = "day9")] day9,
+    #[cfg(feature = "day10")] day10,
+    #[cfg(feature = "day11")] day11,
+    #[cfg(feature = "day12")] day12,
+    #[cfg(feature = "day13")] day13,
+    #[cfg(feature = "day14")] day14,
+    #[cfg(feature = "day15")] day15,
+    #[cfg(feature = "day16")] day16,
+    #[cfg(feature = "day17")] day17,
+    #[cfg(feature = "day18")] day18,
+    #[cfg(feature = "day19")] day19,
+    #[cfg(feature = "day20")] day20,
+    #[cfg(feature = "day21")] day21,
+    #[cfg(feature = "day22")] day22,
+    #[cfg(feature = "day23")] day23,
+}
 
 pub struct DayResult {
-    pub part1: String,
-    pub part2: String,
+    pub part1: Answer,
+    pub part2: Answer,
     pub description: String,
-    pub timing_us: u128,
-}
-
-pub trait DaySolver {
-    fn solve(&self) -> DayResult;
-}
-
-pub fn days() -> Vec<Box<dyn DaySolver>> {
-    let mut days: Vec<Box<dyn DaySolver>> = vec![Box::new(day1::Day {})];
-    days.push(Box::new(day2::Day {}));
-    days.push(Box::new(day3::Day {}));
-    days.push(Box::new(day4::Day {}));
-    days.push(Box::new(day5::Day {}));
-    days.push(Box::new(day6::Day {}));
-    days.push(Box::new(day7::Day {}));
-    days.push(Box::new(day8::Day {}));
-    days.push(Box::new(day9::Day {}));
-    days.push(Box::new(day10::Day {}));
-    days.push(Box::new(day11::Day {}));
-    days.push(Box::new(day12::Day {}));
-    days.push(Box::new(day13::Day {}));
-    days.push(Box::new(day14::Day {}));
-    days.push(Box::new(day15::Day {}));
-    days.push(Box::new(day16::Day {}));
-    days.push(Box::new(day17::Day {}));
-    days.push(Box::new(day18::Day {}));
-    days.push(Box::new(day19::Day {}));
-    days.push(Box::new(day20::Day {}));
-    days.push(Box::new(day21::Day {}));
-    days.push(Box::new(day22::Day {}));
-    days.push(Box::new(day23::Day {}));
-    days
+    pub timings: Timings,
+    // What each part counted, named as "part1.cache_hits" and so on.
+    pub metrics: Metrics,
+    // What the whole day allocated, if built with the alloc-stats feature.
+    pub memory: Option<Memory>,
+}
+
+// How long each phase of a day took, in microseconds.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Timings {
+    pub parse_us: u128,
+    pub part1_us: u128,
+    pub part2_us: u128,
+}
+
+impl Timings {
+    pub fn total_us(&self) -> u128 {
+        self.parse_us + self.part1_us + self.part2_us
+    }
+}
+
+// Counts of the work done finding an answer, such as "states_explored" or "cache_hits", by name.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Metrics(BTreeMap<String, u64>);
+
+impl Metrics {
+    pub fn set(&mut self, name: impl Into<String>, value: u64) {
+        self.0.insert(name.into(), value);
+    }
+
+    pub fn get(&self, name: &str) -> Option<u64> {
+        self.0.get(name).copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, u64)> {
+        self.0.iter().map(|(name, &value)| (name.as_str(), value))
+    }
+
+    // Take in another set of metrics, with their names marked by `prefix`.
+    fn merge(&mut self, prefix: &str, other: Metrics) {
+        for (name, value) in other.0 {
+            self.set(format!("{}.{}", prefix, name), value);
+        }
+    }
+}
+
+// As `name=value` pairs, in name order.
+impl fmt::Display for Metrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pairs = self.iter().map(|(name, value)| format!("{}={}", name, value)).collect::<Vec<String>>();
+        write!(f, "{}", pairs.join(", "))
+    }
+}
+
+// The answer to one part of a day, with a sentence describing it.
+pub struct PartResult {
+    pub answer: Answer,
+    pub description: String,
+    pub metrics: Metrics,
+}
+
+impl PartResult {
+    pub fn new(answer: impl Into<Answer>, description: impl Into<String>) -> Self {
+        Self {
+            answer: answer.into(),
+            description: description.into(),
+            metrics: Metrics::default(),
+        }
+    }
+
+    // Record a count of the work the part did, shown by the runners with --stats.
+    pub fn with_metric(mut self, name: &str, value: u64) -> Self {
+        self.metrics.set(name, value);
+        self
+    }
+}
+
+// Days are shared between the threads of the parallel runner, so must be Send and Sync.
+pub trait DaySolver: Send + Sync {
+    // The puzzle input compiled in from src/data, used when no other input is given.
+    fn bundled_input(&self) -> &'static str;
+
+    fn solve(&self, input: &str) -> Result<DayResult, DayError>;
+
+    // The day's working as frames to play back, or None if it doesn't implement Visualize.
+    fn frames(&self, input: &str) -> Option<Result<Vec<Frame>, DayError>>;
+}
+
+// A day split into its phases. Parsing turns the input into `Parsed`, which both parts then share.
+// Every Solution is a DaySolver, with each phase timed separately.
+pub trait Solution: Send + Sync {
+    type Parsed;
+
+    fn bundled_input(&self) -> &'static str;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError>;
+
+    fn part1(&self, parsed: &Self::Parsed) -> Result<PartResult, DayError>;
+
+    fn part2(&self, parsed: &Self::Parsed) -> Result<PartResult, DayError>;
+
+    // Days that implement Visualize return themselves here, so the runners can find them.
+    fn visualizer(&self) -> Option<&dyn Visualize<Parsed = Self::Parsed>> {
+        None
+    }
+}
+
+// One picture of a day's working, with a line saying what it shows.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    pub caption: String,
+    pub picture: String,
+}
+
+impl Frame {
+    pub fn new(caption: impl Into<String>, picture: impl ToString) -> Self {
+        Self {
+            caption: caption.into(),
+            picture: picture.to_string(),
+        }
+    }
+}
+
+// A Solution that can show its working as a sequence of frames, such as each step of a
+// simulation, for the runners to play back.
+pub trait Visualize: Solution {
+    fn frames(&self, parsed: &Self::Parsed) -> Result<Vec<Frame>, DayError>;
+}
+
+impl<S: Solution> DaySolver for S {
+    fn bundled_input(&self) -> &'static str {
+        Solution::bundled_input(self)
+    }
+
+    fn solve(&self, input: &str) -> Result<DayResult, DayError> {
+        let (solved, memory) = memory::measure(|| -> Result<_, DayError> {
+            let start = Instant::now();
+            let parsed = self.parse(trim_blank_lines(input))?;
+            let parsed_at = Instant::now();
+            let part1 = self.part1(&parsed)?;
+            let part1_at = Instant::now();
+            let part2 = self.part2(&parsed)?;
+            Ok((start, parsed_at, part1_at, Instant::now(), part1, part2))
+        });
+        let (start, parsed_at, part1_at, part2_at, part1, part2) = solved?;
+
+        let elapsed = |from: Instant, to: Instant| to.duration_since(from).as_micros();
+        let mut metrics = Metrics::default();
+        metrics.merge("part1", part1.metrics);
+        metrics.merge("part2", part2.metrics);
+
+        Ok(DayResult {
+            description: format!("{}\n{}", part1.description, part2.description),
+            part1: part1.answer,
+            part2: part2.answer,
+            timings: Timings {
+                parse_us: elapsed(start, parsed_at),
+                part1_us: elapsed(parsed_at, part1_at),
+                part2_us: elapsed(part1_at, part2_at),
+            },
+            metrics,
+            memory,
+        })
+    }
+
+    fn frames(&self, input: &str) -> Option<Result<Vec<Frame>, DayError>> {
+        let visualizer = self.visualizer()?;
+        Some(self.parse(trim_blank_lines(input)).and_then(|parsed| visualizer.frames(&parsed)))
+    }
+}
+
+// Fails to build if the registered days can't be handed across threads.
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync + ?Sized>() {}
+    assert_send_sync::<&'static dyn DaySolver>();
+};
+
+// Load the input for a day and solve it. Any failure is marked with the day.
+pub fn run_day(day: usize, solver: &dyn DaySolver, source: &InputSource) -> Result<DayResult, DayError> {
+    let input = read_day_input(day, solver, source)?;
+    solver.solve(&input).map_err(|e| e.for_day(day))
+}
+
+// Load the input for a day, marking any failure with the day.
+pub fn read_day_input(day: usize, solver: &dyn DaySolver, source: &InputSource) -> Result<String, DayError> {
+    load_input(day, solver, source)
+        .map_err(|e| DayError::new(format!("could not read input: {}", e)).for_day(day))
+}
+
+// The 2021 solutions, keyed by day number.
+pub fn days() -> BTreeMap<usize, Registered> {
+    registrations().into_iter().map(|(_, registered)| (registered.info.day, registered)).collect()
+}
+
+pub fn year2021() -> Year {
+    let mut year = Year::new(2021);
+    for registered in days().into_values() {
+        year.add(registered.info, registered.solver);
+    }
+    for day in compiled_out() {
+        year.compile_out(day);
+    }
+    year
+}
+
+// Every year the runners can solve. Add new years here.
+pub fn registry() -> Registry {
+    let mut registry = Registry::default();
+    registry.add(year2021());
+    registry
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registrations() {
+        let registrations = registrations();
+        for (module, registered) in &registrations {
+            assert_eq!(*module, format!("day{}", registered.info.day), "{} declares the wrong day", module);
+        }
+
+        // Two modules claiming a day would leave one of them out.
+        let days = days();
+        assert_eq!(days.len(), registrations.len());
+
+        // Every day module in the source tree has to be registered, whether it's built or not.
+        let mut files = std::fs::read_dir(crate_dir().join("src"))
+            .unwrap()
+            .filter_map(|entry| entry.unwrap().file_name().into_string().ok())
+            .filter_map(|name| name.strip_prefix("day")?.strip_suffix(".rs")?.parse::<usize>().ok())
+            .collect::<Vec<usize>>();
+        files.sort_unstable();
+        let mut listed = days.keys().copied().chain(compiled_out()).collect::<Vec<usize>>();
+        listed.sort_unstable();
+        assert_eq!(files, listed);
+    }
+
+    struct Counting;
+
+    impl Solution for Counting {
+        type Parsed = u64;
+
+        fn bundled_input(&self) -> &'static str {
+            "3"
+        }
+
+        fn parse(&self, input: &str) -> Result<u64, DayError> {
+            input.parse().map_err(|_| DayError::new("not a number"))
+        }
+
+        fn part1(&self, n: &u64) -> Result<PartResult, DayError> {
+            Ok(PartResult::new(*n, "counted").with_metric("steps", *n))
+        }
+
+        fn part2(&self, n: &u64) -> Result<PartResult, DayError> {
+            Ok(PartResult::new(*n, "counted again").with_metric("steps", 2 * n).with_metric("hits", 1))
+        }
+    }
+
+    #[test]
+    fn test_metrics() {
+        let result = Counting.solve("3").unwrap();
+        assert_eq!(result.metrics.get("part1.steps"), Some(3));
+        assert_eq!(result.metrics.get("part2.steps"), Some(6));
+        assert_eq!(result.metrics.to_string(), "part1.steps=3, part2.hits=1, part2.steps=6");
+    }
 }
@@ -1,54 +1,649 @@
 use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::process;
-use advent_2021::DayResult;
+use std::thread;
+use std::time::Instant;
+use advent_2021::{
+    batch_inputs, batch_table, bench, bench_summary, compare_row, csv_record, json_record, verify, verify_row, worker_count, Command, DayError,
+    BatchResult, Change, DayResult, DaySolver, Entry, Expected, Format, History, InputSource, Manifest, Options, Poller, Record,
+    Registry, Report, ReportDay, Run, Timings, Verification, Watch, Year, COMPARE_HEADER, CSV_HEADER, DEFAULT_HISTORY, DEFAULT_MANIFEST, HISTORY_HEADER,
+    VERIFY_HEADER,
+};
+
+// Counts what each day allocates, for the memory stats.
+#[cfg(feature = "alloc-stats")]
+#[global_allocator]
+static ALLOCATOR: advent_2021::Counting = advent_2021::Counting;
 
 fn main() {
-    match day_arg(env::args()) {
-        Some(0) | None => {
-            for (i, day) in advent_2021::days().iter().enumerate() {
-                pretty_print_day(i, &day.solve());
+    let options = match Options::parse(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}", e);
+            eprintln!("Usage: advent-2021 [bench|verify|new-day] [day] [--year <year>] [--input <file>|-] [--input-dir <dir>]");
+            eprintln!("       bench options: [--runs <n>] [--warmup <n>] [--budget-ms <ms>]");
+            eprintln!("       run options: [--format json|csv|text] [--stats] [--timeout <ms>] [--watch] [--jobs <n>]");
+            eprintln!("       tracing, on stderr: [-v|-vv] [--trace day<n>]");
+            eprintln!("       history: [--record] [--history <file>] [--machine <label>]");
+            eprintln!("       advent-2021 compare [day] [--baseline <revision>] [--threshold <percent>] [--history <file>] [--machine <label>]");
+            eprintln!("       advent-2021 report [day] [--html] [--output <file>] [--readme <file>]");
+            eprintln!("       advent-2021 batch <dir> [day] [--timeout <ms>], with inputs in <dir>/day<n>/<name>.dat");
+            eprintln!("       advent-2021 fetch [day] [--year <year>] [--base-url <url>] [--session-file <file>] [--cache-dir <dir>], with the fetch feature");
+            eprintln!("       verify options: [--manifest <file>] [--write]");
+            eprintln!("       advent-2021 tui [day] [--year <year>] [--input-dir <dir>] [--timeout <ms>], with the color feature");
+            eprintln!("       advent-2021 play <day> [--year <year>] [--input <file>], with the color feature");
+            process::exit(1);
+        }
+    };
+
+    options.tracing.install();
+
+    if options.command == Command::Compare {
+        process::exit(if compare_runs(&options) { 0 } else { 1 });
+    }
+
+    if options.command == Command::NewDay {
+        process::exit(if new_day(options.year, options.day.unwrap_or(0)) { 0 } else { 1 });
+    }
+
+    let registry = advent_2021::registry();
+    let year = match registry.year(options.year) {
+        Ok(year) => year,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    };
+    if options.command == Command::Tui {
+        process::exit(if run_tui(year, &options) { 0 } else { 1 });
+    }
+    if options.command == Command::Play {
+        process::exit(if play_day(year, &options) { 0 } else { 1 });
+    }
+    if options.command == Command::Fetch {
+        process::exit(if fetch_inputs(year, &options) { 0 } else { 1 });
+    }
+    if options.command == Command::Batch {
+        process::exit(if batch_days(year, &options) { 0 } else { 1 });
+    }
+    if options.command == Command::Report {
+        process::exit(if write_report(year, &options) { 0 } else { 1 });
+    }
+    if options.command == Command::Verify {
+        let passed = if options.write {
+            write_manifest(year, &options)
+        } else {
+            verify_manifest(&registry, &options)
+        };
+        process::exit(if passed { 0 } else { 1 });
+    }
+    if options.command == Command::Run && options.format == Format::Csv {
+        println!("{}", CSV_HEADER);
+    }
+    // Every day solved by this invocation is recorded as one run.
+    let recording = if options.record {
+        Some(Run::start(options.machine.as_deref()))
+    } else {
+        None
+    };
+    let recording = recording.as_ref();
+    let all_solved = match options.day {
+        None => {
+            let days = year.solved();
+            let failed = match options.jobs {
+                Some(jobs) => solve_all_parallel(year.year, &days, jobs, &options, recording),
+                None => {
+                    let mut failed = 0;
+                    for &(day, solver) in &days {
+                        if !run_day(year.year, day, solver, &options, recording) {
+                            failed += 1;
+                        }
+                    }
+                    failed
+                }
+            };
+            if failed > 0 {
+                eprintln!("{} of {} days failed", failed, days.len());
             }
+            report_compiled_out(year);
+            failed == 0
         },
-        Some(i) => {
-            let days = advent_2021::days();
-            if i as usize > days.len() {
-                eprintln!("Unimplemented day {}", i);
+        Some(day) => match year.get(day) {
+            Ok(solver) if options.watch => watch_day(year.year, day, solver, &options),
+            Ok(solver) => run_day(year.year, day, solver, &options, recording),
+            Err(e) => {
+                eprintln!("{}", e);
                 process::exit(1);
-            } else {
-                pretty_print_day((i - 1) as usize, &days[(i - 1) as usize].solve());
             }
         }
+    };
+
+    if !all_solved {
+        process::exit(1);
     }
 }
 
-fn pretty_print_day(day_index: usize, solution: &DayResult) {
-    println!("Day {} : {}\n Solved in {}\n",
-             day_index + 1,
-             solution.description,
-             pretty_us(solution.timing_us));
+// Run a single day, reporting whether it succeeded.
+fn run_day(year: usize, day: usize, solver: &'static dyn DaySolver, options: &Options, recording: Option<&Run>) -> bool {
+    match options.command {
+        Command::Run => solve_day(year, day, solver, options, recording),
+        Command::Bench => bench_day(day, solver, options),
+        Command::Verify => unreachable!("verify works from the manifest"),
+        Command::NewDay => unreachable!("new-day doesn't run any days"),
+        Command::Tui => unreachable!("the tui runs its own days"),
+        Command::Play => unreachable!("play shows a day's working rather than running it"),
+        Command::Compare => unreachable!("compare works from the history"),
+        Command::Report => unreachable!("report solves the days itself"),
+        Command::Batch => unreachable!("batch solves each day on many inputs"),
+        Command::Fetch => unreachable!("fetch downloads inputs rather than solving them"),
+    }
 }
 
-fn pretty_us(micros: u128) -> String {
-    if micros > 1_000_000 {
-        format!("{}s", micros / 1_000_000)
-    } else if micros > 1000 {
-        format!("{}ms", micros / 1000)
+fn solve_day(year: usize, day: usize, solver: &'static dyn DaySolver, options: &Options, recording: Option<&Run>) -> bool {
+    let result = advent_2021::run_day_watched(day, solver, &options.input, Watch::new(options.timeout));
+    print_result(year, day, &result, options);
+    result.is_ok() && record_result(year, day, &result, options, recording)
+}
+
+// Solve a day, then again each time its input changes, until interrupted. Each solution is
+// compared with the last one that worked. The solver can't change without a rebuild, so changes
+// to its source are only pointed out.
+fn watch_day(year: usize, day: usize, solver: &'static dyn DaySolver, options: &Options) -> bool {
+    let input = advent_2021::watched_input(day, &options.input).expect("--watch rejects stdin");
+    let source_file = advent_2021::crate_dir().join("src").join(format!("day{}.rs", day));
+    let mut poller = Poller::new(vec![input.clone(), source_file.clone()]);
+    let source = advent_2021::rerun_source(day, &options.input);
+    let mut previous: Option<Timings> = None;
+    loop {
+        let result = advent_2021::run_day_watched(day, solver, &source, Watch::new(options.timeout));
+        print_result(year, day, &result, options);
+        if let Ok(solution) = &result {
+            if let Some(before) = previous {
+                let change = Change {
+                    year,
+                    day,
+                    before_us: before.total_us(),
+                    after_us: solution.timings.total_us(),
+                };
+                println!(
+                    "{} this time, against {} before ({:+.1}%)\n",
+                    pretty_us(change.after_us),
+                    pretty_us(change.before_us),
+                    change.percent()
+                );
+            }
+            previous = Some(solution.timings);
+        }
+        println!("Watching {} for changes, Ctrl-C to stop", input.display());
+
+        loop {
+            thread::sleep(advent_2021::POLL_INTERVAL);
+            let changed = poller.changed();
+            if changed.contains(&source_file) {
+                println!("{} changed, rebuild to solve with it", source_file.display());
+            }
+            if changed.contains(&input) {
+                break;
+            }
+        }
+    }
+}
+
+// Solve every day on a pool of threads, returning how many failed. The wall clock time
+// is reported against each day's own wall clock time summed over every day. Days share
+// the CPUs, so the sum isn't the CPU time they used.
+fn solve_all_parallel(
+    year: usize,
+    days: &[(usize, &'static dyn DaySolver)],
+    jobs: usize,
+    options: &Options,
+    recording: Option<&Run>,
+) -> usize {
+    let start = Instant::now();
+    let mut failed = 0;
+    let mut summed_us = 0;
+    advent_2021::run_parallel(days, &options.input, jobs, options.timeout, |day, result| {
+        match &result {
+            Ok(solution) => summed_us += solution.timings.total_us(),
+            Err(_) => failed += 1,
+        }
+        print_result(year, day, &result, options);
+        if result.is_ok() && !record_result(year, day, &result, options, recording) {
+            failed += 1;
+        }
+    });
+
+    let summary = format!(
+        "All days in {} wall clock, {} of wall clock summed over days, on {} threads",
+        pretty_us(start.elapsed().as_micros()),
+        pretty_us(summed_us),
+        worker_count(jobs).min(days.len())
+    );
+    // Keep machine readable output clean.
+    match options.format {
+        Format::Text => println!("{}", summary),
+        _ => eprintln!("{}", summary),
+    }
+    failed
+}
+
+// Days left out by turning off their features aren't failures, but say they weren't run.
+fn report_compiled_out(year: &Year) {
+    let days = year.compiled_out();
+    if !days.is_empty() {
+        let days = days.iter().map(|d| d.to_string()).collect::<Vec<String>>();
+        let label = if days.len() == 1 { "day" } else { "days" };
+        eprintln!("Compiled out of this build: {} {}", label, days.join(", "));
+    }
+}
+
+#[cfg(feature = "color")]
+fn run_tui(year: &Year, options: &Options) -> bool {
+    match advent_2021::run_tui(year, &options.input, options.day, options.timeout) {
+        Ok(()) => true,
+        Err(e) => {
+            eprintln!("{}", e);
+            false
+        }
+    }
+}
+
+#[cfg(not(feature = "color"))]
+fn run_tui(_: &Year, _: &Options) -> bool {
+    eprintln!("The tui needs the color feature: cargo run --features color -- tui");
+    false
+}
+
+// Play back the frames from a day that implements Visualize.
+#[cfg(feature = "color")]
+fn play_day(year: &Year, options: &Options) -> bool {
+    let day = options.day.unwrap_or(0);
+    let frames = year.get(day).and_then(|solver| {
+        let input = advent_2021::read_day_input(day, solver, &options.input)?;
+        solver.frames(&input).ok_or_else(|| DayError::new("nothing to play back").for_day(day))?.map_err(|e| e.for_day(day))
+    });
+    let played = frames.map_err(|e| e.to_string()).and_then(|frames| advent_2021::play(&frames).map_err(|e| e.to_string()));
+    if let Err(e) = &played {
+        eprintln!("{}", e);
+    }
+    played.is_ok()
+}
+
+#[cfg(not(feature = "color"))]
+fn play_day(_: &Year, _: &Options) -> bool {
+    eprintln!("play needs the color feature: cargo run --features color -- play <day>");
+    false
+}
+
+// Download the input for the given day, or every solved day of the year, skipping any already
+// downloaded.
+#[cfg(feature = "fetch")]
+fn fetch_inputs(year: &Year, options: &Options) -> bool {
+    use advent_2021::{Fetched, Fetcher, DEFAULT_BASE_URL, DEFAULT_CACHE_DIR, DEFAULT_SESSION_FILE};
+
+    let config = &options.fetch;
+    let session_file = config.session_file.clone().unwrap_or_else(|| PathBuf::from(DEFAULT_SESSION_FILE));
+    let fetcher = Fetcher::new(
+        config.base_url.as_deref().unwrap_or(DEFAULT_BASE_URL),
+        config.cache_dir.as_deref().unwrap_or(Path::new(DEFAULT_CACHE_DIR)),
+        advent_2021::session_token(&session_file),
+    );
+    let days = match options.day {
+        Some(day) if year.entry(day).is_none() => {
+            eprintln!("{}", DayError::new(format!("not in the {} calendar", year.year)).for_day(day));
+            return false;
+        }
+        Some(day) => vec![day],
+        None => year.solved().into_iter().map(|(day, _)| day).collect(),
+    };
+    let mut all_fetched = true;
+    for day in days {
+        match fetcher.fetch(year.year, day) {
+            Ok((path, Fetched::Downloaded)) => println!("Downloaded day {} to {}", day, path.display()),
+            Ok((path, Fetched::Cached)) => println!("Day {} is already in {}", day, path.display()),
+            Err(e) => {
+                eprintln!("{}", e);
+                all_fetched = false;
+            }
+        }
+    }
+    all_fetched
+}
+
+#[cfg(not(feature = "fetch"))]
+fn fetch_inputs(_: &Year, _: &Options) -> bool {
+    eprintln!("fetch needs the fetch feature: cargo run --features fetch -- fetch <day>");
+    false
+}
+
+// Add the files for a new day to the source tree.
+fn new_day(year: Option<usize>, day: usize) -> bool {
+    // The scaffold only knows how to register days in this crate.
+    if year.is_some_and(|year| year != 2021) {
+        eprintln!("new-day can only add days to 2021");
+        return false;
+    }
+    match advent_2021::scaffold_day(&advent_2021::crate_dir(), day) {
+        Ok(created) => {
+            for path in created {
+                println!("Created {}", path.display());
+            }
+            println!("Registered day {} in src/lib.rs", day);
+            true
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            false
+        }
+    }
+}
+
+fn manifest_path(options: &Options) -> PathBuf {
+    options.manifest.clone().unwrap_or_else(|| PathBuf::from(DEFAULT_MANIFEST))
+}
+
+fn read_manifest(path: &Path) -> Result<Manifest, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    Manifest::parse(&text).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+// Check the answers listed in the manifest, printing a pass/fail table.
+fn verify_manifest(registry: &Registry, options: &Options) -> bool {
+    let manifest = match read_manifest(&manifest_path(options)) {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            eprintln!("{}", e);
+            return false;
+        }
+    };
+    let entries = manifest
+        .entries
+        .iter()
+        .filter(|e| options.year.is_none_or(|year| year == e.year))
+        .filter(|e| options.day.is_none_or(|day| day == e.day))
+        .collect::<Vec<&Expected>>();
+
+    println!("{}", VERIFY_HEADER);
+    let mut passed = 0;
+    let mut skipped = 0;
+    for expected in &entries {
+        let year = registry.year(Some(expected.year));
+        let verification = match year.as_ref().map(|year| year.entry(expected.day)) {
+            Ok(Some(Entry::CompiledOut)) => Verification::Skipped(String::from("compiled out")),
+            _ => match year.map_err(DayError::new).and_then(|year| year.get(expected.day)) {
+                Ok(solver) => verify(solver, expected),
+                Err(e) => Verification::Failed(e.for_day(expected.day)),
+            },
+        };
+        match verification {
+            Verification::Skipped(_) => skipped += 1,
+            _ if verification.passed() => passed += 1,
+            _ => (),
+        }
+        println!("{}", verify_row(expected, &verification));
+    }
+    if skipped > 0 {
+        println!("\n{} of {} passed, {} skipped", passed, entries.len() - skipped, skipped);
     } else {
-        format!("{}us", micros)
+        println!("\n{} of {} passed", passed, entries.len());
+    }
+    passed + skipped == entries.len()
+}
+
+// Solve the selected days and record their answers in the manifest, keeping any others already there.
+fn write_manifest(year: &Year, options: &Options) -> bool {
+    if options.input == InputSource::Stdin {
+        eprintln!("Answers for input read from stdin can't be recorded");
+        return false;
+    }
+    let path = manifest_path(options);
+    let mut manifest = if path.exists() {
+        match read_manifest(&path) {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                eprintln!("{}", e);
+                return false;
+            }
+        }
+    } else {
+        Manifest::default()
+    };
+
+    let mut all_solved = true;
+    for (day, solver) in year.solved() {
+        if options.day.is_some_and(|only| only != day) {
+            continue;
+        }
+        match advent_2021::run_day(day, solver, &options.input) {
+            Ok(result) => manifest.extend(Expected::from_result(year.year, day, &options.input, &result)),
+            Err(e) => {
+                eprintln!("{}", e);
+                all_solved = false;
+            }
+        }
+    }
+
+    if let Err(e) = fs::write(&path, manifest.to_string()) {
+        eprintln!("{}: {}", path.display(), e);
+        return false;
+    }
+    println!("Wrote {} answers to {}", manifest.entries.len(), path.display());
+    all_solved
+}
+
+// Solve the selected days and write up the results, to stdout, --output or a section of --readme.
+// Days that fail are listed as failed, and make the report fail once it's written.
+fn write_report(year: &Year, options: &Options) -> bool {
+    let days = year
+        .solved()
+        .into_iter()
+        .filter(|(day, _)| options.day.is_none_or(|only| only == *day))
+        .map(|(day, solver)| ReportDay {
+            day,
+            title: year.title(day).unwrap_or_default(),
+            result: advent_2021::run_day(day, solver, &options.input),
+        })
+        .collect::<Vec<ReportDay>>();
+    let all_solved = days.iter().all(|day| day.result.is_ok());
+    let report = Report {
+        year: year.year,
+        revision: advent_2021::git_revision(),
+        days,
+    };
+    let text = if options.html { report.html() } else { report.markdown() };
+
+    let written = match (&options.output, &options.readme) {
+        (None, None) => {
+            print!("{}", text);
+            Ok(())
+        }
+        (output, readme) => output
+            .iter()
+            .try_for_each(|path| fs::write(path, &text).map_err(|e| format!("{}: {}", path.display(), e)))
+            .and_then(|()| readme.iter().try_for_each(|path| update_readme(path, &text))),
+    };
+    if let Err(e) = &written {
+        eprintln!("{}", e);
+    }
+    report_compiled_out(year);
+    written.is_ok() && all_solved
+}
+
+fn update_readme(path: &Path, section: &str) -> Result<(), String> {
+    let error = |e: String| format!("{}: {}", path.display(), e);
+    let text = fs::read_to_string(path).map_err(|e| error(e.to_string()))?;
+    let updated = advent_2021::update_section(&text, section).map_err(error)?;
+    fs::write(path, updated).map_err(|e| error(e.to_string()))?;
+    println!("Updated the report in {}", path.display());
+    Ok(())
+}
+
+// Solve the selected days on every input in the batch directory, printing a table of the
+// answers with anything that failed or didn't match underneath.
+fn batch_days(year: &Year, options: &Options) -> bool {
+    let dir = options.batch_dir.as_deref().expect("batch always has a directory");
+    let mut results = vec![];
+    for (day, solver) in year.solved() {
+        if options.day.is_some_and(|only| only != day) {
+            continue;
+        }
+        match batch_inputs(dir, day) {
+            Ok(inputs) => {
+                results.extend(inputs.iter().map(|input| advent_2021::solve_batch(day, solver, input, options.timeout)))
+            }
+            Err(e) => {
+                eprintln!("{}: {}", dir.join(format!("day{}", day)).display(), e);
+                return false;
+            }
+        }
+    }
+    if results.is_empty() {
+        eprintln!("No inputs found in {}, expected files like day1/<name>.dat", dir.display());
+        return false;
     }
+    println!("{}", batch_table(&results));
+    report_compiled_out(year);
+    results.iter().all(BatchResult::passed)
+}
+
+fn history_path(options: &Options) -> PathBuf {
+    options.history.clone().unwrap_or_else(|| PathBuf::from(DEFAULT_HISTORY))
 }
 
-fn day_arg(mut args: env::Args) -> Option<u8> {
-    args.next();
+// Add a solved day's timings to the history, if this run is being recorded. Days that failed
+// have no timings to keep.
+fn record_result(year: usize, day: usize, result: &Result<DayResult, DayError>, options: &Options, recording: Option<&Run>) -> bool {
+    let (run, solution) = match (recording, result) {
+        (Some(run), Ok(solution)) => (run, solution),
+        _ => return true,
+    };
+    let path = history_path(options);
+    let appended = OpenOptions::new().create(true).append(true).open(&path).and_then(|mut file| {
+        // A new history starts with its header.
+        if file.metadata()?.len() == 0 {
+            writeln!(file, "{}", HISTORY_HEADER)?;
+        }
+        writeln!(file, "{}", Record::new(run, year, day, solution))
+    });
+    if let Err(e) = &appended {
+        eprintln!("{}: {}", path.display(), e);
+    }
+    appended.is_ok()
+}
 
-    let day = match args.next() {
-        Some(day) => day.parse::<u8>(),
-        None => return None,
+// Compare the latest run in the history with the one before, or the baseline, printing a table
+// of each day's timings and failing if any got slower than the threshold.
+fn compare_runs(options: &Options) -> bool {
+    let path = history_path(options);
+    let history = fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|text| History::parse(&text))
+        .map_err(|e| format!("{}: {}", path.display(), e));
+    let machine = options.machine.clone().unwrap_or_else(advent_2021::machine_label);
+    let pair = history.and_then(|history| {
+        let (before, after) = history.latest_pair(&machine, options.compare.baseline.as_deref())?;
+        Ok((history.compare(&before, &after), before, after))
+    });
+    let (changes, before, after) = match pair {
+        Ok(pair) => pair,
+        Err(e) => {
+            eprintln!("{}", e);
+            return false;
+        }
     };
 
-    if let Ok(i) = day {
-        return Some(i);
+    let threshold = options.compare.threshold;
+    println!("Comparing {} with {}, flagging days over {}% slower\n", after, before.revision, threshold);
+    println!("{}", COMPARE_HEADER);
+    let mut regressed = 0;
+    for change in changes
+        .iter()
+        .filter(|c| options.year.is_none_or(|year| year == c.year))
+        .filter(|c| options.day.is_none_or(|day| day == c.day))
+    {
+        if change.regressed(threshold) {
+            regressed += 1;
+        }
+        println!("{}", compare_row(change, threshold));
+    }
+    if regressed > 0 {
+        println!("\n{} {} slower", regressed, if regressed == 1 { "day got" } else { "days got" });
+    }
+    regressed == 0
+}
+
+fn print_result(year: usize, day: usize, result: &Result<DayResult, DayError>, options: &Options) {
+    match (options.format, result) {
+        (Format::Text, Ok(solution)) => pretty_print_day(day, solution, options.stats),
+        (Format::Text, Err(e)) => eprintln!("{}\n", e),
+        (Format::Json, _) => println!("{}", json_record(year, day, result)),
+        (Format::Csv, _) => println!("{}", csv_record(year, day, result)),
+    }
+}
+
+fn bench_day(day: usize, solver: &dyn DaySolver, options: &Options) -> bool {
+    let result = advent_2021::read_day_input(day, solver, &options.input)
+        .and_then(|input| bench(solver, &input, &options.bench).map_err(|e| e.for_day(day)));
+    match result {
+        Ok(result) => {
+            println!("{}", bench_summary(day, &result));
+            true
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            false
+        }
+    }
+}
+
+#[cfg(feature = "color")]
+fn pretty_print_day(day: usize, solution: &DayResult, stats: bool) {
+    advent_2021::print_day(day);
+    let timings = &solution.timings;
+    println!(
+        "{}\n\t[{} : parse {}, part 1 {}, part 2 {}]",
+        advent_2021::brighten(&solution.description, &solution.part1, &solution.part2),
+        pretty_us(timings.total_us()),
+        pretty_us(timings.parse_us),
+        pretty_us(timings.part1_us),
+        pretty_us(timings.part2_us)
+    );
+    if let Some(memory) = solution.memory {
+        println!("\t[{}]", memory);
+    }
+    if stats && !solution.metrics.is_empty() {
+        println!("\t[{}]", solution.metrics);
     }
+    println!();
+}
 
-    None
+#[cfg(not(feature = "color"))]
+fn pretty_print_day(day: usize, solution: &DayResult, stats: bool) {
+    let timings = &solution.timings;
+    println!("Day {} : {}\n Solved in {} (parse {}, part 1 {}, part 2 {})",
+             day,
+             solution.description,
+             pretty_us(timings.total_us()),
+             pretty_us(timings.parse_us),
+             pretty_us(timings.part1_us),
+             pretty_us(timings.part2_us));
+    if let Some(memory) = solution.memory {
+        println!(" Memory: {}", memory);
+    }
+    if stats && !solution.metrics.is_empty() {
+        println!(" Stats: {}", solution.metrics);
+    }
+    println!();
+}
+
+fn pretty_us(micros: u128) -> String {
+    if micros > 1_000_000 {
+        format!("{}s", micros / 1_000_000)
+    } else if micros > 1000 {
+        format!("{}ms", micros / 1000)
+    } else {
+        format!("{}us", micros)
+    }
 }
@@ -0,0 +1,427 @@
+use crate::{column_of, run_day, Answer, DayError, DayResult, DaySolver, InputSource};
+use std::fmt;
+use std::path::PathBuf;
+
+// Where verify looks for expected answers, relative to where the runner is started.
+pub const DEFAULT_MANIFEST: &str = "answers.toml";
+
+// The answers expected for one day on one input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expected {
+    pub year: usize,
+    pub day: usize,
+    // None for the bundled input, otherwise a file relative to where the runner is started.
+    pub input: Option<PathBuf>,
+    pub part1: Answer,
+    pub part2: Answer,
+}
+
+impl Expected {
+    pub fn input_name(&self) -> String {
+        match &self.input {
+            Some(path) => path.display().to_string(),
+            None => String::from("bundled"),
+        }
+    }
+
+    // The entry for a day solved from `source`. Answers read from stdin can't be checked again, so give None.
+    pub fn from_result(year: usize, day: usize, source: &InputSource, result: &DayResult) -> Option<Self> {
+        let input = match source {
+            InputSource::Bundled => None,
+            InputSource::File(path) => Some(path.clone()),
+            InputSource::Dir(dir) => Some(dir.join(format!("day{}.dat", day))).filter(|p| p.is_file()),
+            InputSource::Stdin => return None,
+        };
+        Some(Self {
+            year,
+            day,
+            input,
+            part1: result.part1.clone(),
+            part2: result.part2.clone(),
+        })
+    }
+
+    fn key(&self) -> (usize, usize, &Option<PathBuf>) {
+        (self.year, self.day, &self.input)
+    }
+
+    fn source(&self) -> InputSource {
+        match &self.input {
+            Some(path) => InputSource::File(path.clone()),
+            None => InputSource::Bundled,
+        }
+    }
+}
+
+// A list of expected answers, kept as a small subset of TOML:
+//
+//   [[answer]]
+//   year = 2021
+//   day = 13
+//   input = "inputs/day13.dat"   # left out for the bundled input
+//   part1 = 765
+//   part2 = ["# #", " # "]       # a grid answer, one string per row
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Manifest {
+    pub entries: Vec<Expected>,
+}
+
+impl Manifest {
+    pub fn parse(text: &str) -> Result<Self, DayError> {
+        let mut entries = vec![];
+        let mut current: Option<(usize, Partial)> = None;
+        for (i, raw) in text.lines().enumerate() {
+            let line_no = i + 1;
+            let line = strip_comment(raw).trim();
+            if line.is_empty() {
+                continue;
+            }
+            if line == "[[answer]]" {
+                if let Some((start, partial)) = current.take() {
+                    entries.push(partial.finish(start)?);
+                }
+                current = Some((line_no, Partial::default()));
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| DayError::at(line_no, column_of(raw, line), "expected '[[answer]]' or 'key = value'"))?;
+            let (key, value) = (key.trim(), value.trim());
+            let partial = match current.as_mut() {
+                Some((_, partial)) => partial,
+                None => return Err(DayError::at(line_no, column_of(raw, key), "expected '[[answer]]' before any values")),
+            };
+            let value = parse_value(raw, value).map_err(|e| e.on_line(line_no))?;
+            let at = |reason: String| DayError::at(line_no, column_of(raw, key), reason);
+            match (key, value) {
+                ("year", Answer::Integer(year)) if year > 0 => partial.year = Some(year as usize),
+                ("day", Answer::Integer(day)) if day > 0 => partial.day = Some(day as usize),
+                ("input", Answer::Text(path)) => partial.input = Some(PathBuf::from(path)),
+                ("part1", answer) => partial.part1 = Some(answer),
+                ("part2", answer) => partial.part2 = Some(answer),
+                ("year", _) | ("day", _) | ("input", _) => return Err(at(format!("'{}' has the wrong type", key))),
+                _ => return Err(at(format!("unknown key '{}'", key))),
+            }
+        }
+        if let Some((start, partial)) = current {
+            entries.push(partial.finish(start)?);
+        }
+        Ok(Self { entries })
+    }
+
+    pub fn extend<I: IntoIterator<Item = Expected>>(&mut self, entries: I) {
+        for expected in entries {
+            self.record(expected);
+        }
+    }
+
+    // Add or replace the answers expected for a day and input.
+    pub fn record(&mut self, expected: Expected) {
+        match self.entries.iter_mut().find(|e| e.key() == expected.key()) {
+            Some(entry) => *entry = expected,
+            None => self.entries.push(expected),
+        }
+        self.entries.sort_by(|a, b| a.key().cmp(&b.key()));
+    }
+}
+
+impl fmt::Display for Manifest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# Expected answers, checked by `verify` and written by `verify --write`.")?;
+        for entry in &self.entries {
+            writeln!(f, "\n[[answer]]")?;
+            writeln!(f, "year = {}", entry.year)?;
+            writeln!(f, "day = {}", entry.day)?;
+            if let Some(path) = &entry.input {
+                writeln!(f, "input = {}", toml_string(&path.display().to_string()))?;
+            }
+            writeln!(f, "part1 = {}", toml_value(&entry.part1))?;
+            writeln!(f, "part2 = {}", toml_value(&entry.part2))?;
+        }
+        Ok(())
+    }
+}
+
+// The answers expected for a single input, kept next to it: the `part1` and `part2` lines of a
+// manifest entry, either of which can be left out if it isn't known.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Answers {
+    pub part1: Option<Answer>,
+    pub part2: Option<Answer>,
+}
+
+impl Answers {
+    pub fn parse(text: &str) -> Result<Self, DayError> {
+        let mut answers = Self::default();
+        for (i, raw) in text.lines().enumerate() {
+            let line_no = i + 1;
+            let line = strip_comment(raw).trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| DayError::at(line_no, column_of(raw, line), "expected 'part1 = ...' or 'part2 = ...'"))?;
+            let key = key.trim();
+            let value = parse_value(raw, value.trim()).map_err(|e| e.on_line(line_no))?;
+            match key {
+                "part1" => answers.part1 = Some(value),
+                "part2" => answers.part2 = Some(value),
+                _ => return Err(DayError::at(line_no, column_of(raw, key), format!("unknown key '{}'", key))),
+            }
+        }
+        Ok(answers)
+    }
+
+    // Whether each part of `result` matches, where a part without an answer can't be wrong.
+    pub fn check(&self, result: &DayResult) -> (bool, bool) {
+        let matches = |expected: &Option<Answer>, found: &Answer| expected.as_ref().is_none_or(|e| e == found);
+        (matches(&self.part1, &result.part1), matches(&self.part2, &result.part2))
+    }
+}
+
+// An entry part way through being read.
+#[derive(Default)]
+struct Partial {
+    year: Option<usize>,
+    day: Option<usize>,
+    input: Option<PathBuf>,
+    part1: Option<Answer>,
+    part2: Option<Answer>,
+}
+
+impl Partial {
+    fn finish(self, line_no: usize) -> Result<Expected, DayError> {
+        match (self.year, self.day, self.part1, self.part2) {
+            (Some(year), Some(day), Some(part1), Some(part2)) => Ok(Expected {
+                year,
+                day,
+                input: self.input,
+                part1,
+                part2,
+            }),
+            _ => Err(DayError::at(line_no, 1, "each answer needs a year, day, part1 and part2")),
+        }
+    }
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => (),
+        }
+    }
+    line
+}
+
+// An integer, a string, or an array of strings for a grid. `value` is a slice of `line`,
+// which errors are positioned against.
+fn parse_value(line: &str, value: &str) -> Result<Answer, DayError> {
+    let error = |at: &str, reason: &str| DayError::new(reason).at_column(column_of(line, at));
+    if let Some(inner) = value.strip_prefix('[') {
+        let inner = inner
+            .strip_suffix(']')
+            .ok_or_else(|| error(value, "expected ']' to close the array"))?;
+        let mut rows = vec![];
+        let mut rest = inner.trim_start();
+        while !rest.is_empty() {
+            let (row, after) = parse_string(line, rest)?;
+            rows.push(row);
+            rest = after.trim_start();
+            rest = match rest.strip_prefix(',') {
+                Some(after_comma) => after_comma.trim_start(),
+                None if rest.is_empty() => rest,
+                None => return Err(error(rest, "expected ',' between array items")),
+            };
+        }
+        return Ok(Answer::Grid(rows));
+    }
+    if value.starts_with('"') {
+        let (text, rest) = parse_string(line, value)?;
+        if !rest.trim().is_empty() {
+            return Err(error(rest, "unexpected characters after the string"));
+        }
+        return Ok(Answer::Text(text));
+    }
+    value
+        .replace('_', "")
+        .parse::<i128>()
+        .map(Answer::Integer)
+        .map_err(|_| error(value, &format!("could not parse '{}'", value)))
+}
+
+// Read a double quoted string from the start of `s`, returning it and whatever follows.
+fn parse_string<'a>(line: &str, s: &'a str) -> Result<(String, &'a str), DayError> {
+    let error = |at: &str, reason: &str| DayError::new(reason).at_column(column_of(line, at));
+    let mut chars = s.char_indices();
+    if !matches!(chars.next(), Some((_, '"'))) {
+        return Err(error(s, "expected a string"));
+    }
+    let mut text = String::new();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Ok((text, &s[i + 1..])),
+            '\\' => match chars.next() {
+                Some((_, 'n')) => text.push('\n'),
+                Some((_, 't')) => text.push('\t'),
+                Some((_, '"')) => text.push('"'),
+                Some((_, '\\')) => text.push('\\'),
+                _ => return Err(error(&s[i..], "unknown escape in string")),
+            },
+            c => text.push(c),
+        }
+    }
+    Err(error(s, "unterminated string"))
+}
+
+fn toml_value(answer: &Answer) -> String {
+    match answer {
+        Answer::Integer(value) => value.to_string(),
+        Answer::Text(text) => toml_string(text),
+        Answer::Grid(rows) => format!(
+            "[{}]",
+            rows.iter().map(|r| toml_string(r)).collect::<Vec<String>>().join(", ")
+        ),
+    }
+}
+
+fn toml_string(s: &str) -> String {
+    format!(
+        "\"{}\"",
+        s.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n").replace('\t', "\\t")
+    )
+}
+
+// The outcome of checking one manifest entry.
+pub enum Verification {
+    // Whether each part matched, with what was actually found.
+    Checked { part1: bool, part2: bool, result: DayResult },
+    Failed(DayError),
+    // Not checked, such as a day left out of this build.
+    Skipped(String),
+}
+
+impl Verification {
+    pub fn passed(&self) -> bool {
+        matches!(self, Verification::Checked { part1: true, part2: true, .. })
+    }
+}
+
+pub fn verify(solver: &dyn DaySolver, expected: &Expected) -> Verification {
+    match run_day(expected.day, solver, &expected.source()) {
+        Ok(result) => Verification::Checked {
+            part1: result.part1 == expected.part1,
+            part2: result.part2 == expected.part2,
+            result,
+        },
+        Err(e) => Verification::Failed(e),
+    }
+}
+
+// A row of the verify table, with what went wrong underneath any failure.
+pub fn verify_row(expected: &Expected, verification: &Verification) -> String {
+    let mark = |ok: bool| if ok { "pass" } else { "FAIL" };
+    let mut row = format!("{:>4}  {:>3}  {:<28}", expected.year, expected.day, expected.input_name());
+    match verification {
+        Verification::Checked { part1, part2, result } => {
+            row.push_str(&format!("  {:<6}  {}", mark(*part1), mark(*part2)));
+            if !part1 {
+                row.push_str(&format!("\n             part 1 expected {}, got {}", expected.part1, result.part1));
+            }
+            if !part2 {
+                row.push_str(&format!("\n             part 2 expected {}, got {}", expected.part2, result.part2));
+            }
+        }
+        Verification::Failed(e) => row.push_str(&format!("  error\n             {}", e)),
+        Verification::Skipped(why) => row.push_str(&format!("  skipped, {}", why)),
+    }
+    row
+}
+
+pub const VERIFY_HEADER: &str = "Year  Day  Input                         Part 1  Part 2";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r##"
+# Some answers.
+[[answer]]
+year = 2021
+day = 1
+part1 = 7       # the example
+part2 = 5
+
+[[answer]]
+year = 2022
+day = 13
+input = "inputs/day13 #2.dat"
+part1 = -1_000
+part2 = ["# #", " \"#"]
+"##;
+
+    #[test]
+    fn test_parse() {
+        let manifest = Manifest::parse(MANIFEST).unwrap();
+        assert_eq!(manifest.entries.len(), 2);
+        assert_eq!(manifest.entries[0].part1, Answer::Integer(7));
+        assert_eq!(manifest.entries[0].input_name(), "bundled");
+        assert_eq!((manifest.entries[0].year, manifest.entries[1].year), (2021, 2022));
+        assert_eq!(manifest.entries[1].input, Some(PathBuf::from("inputs/day13 #2.dat")));
+        assert_eq!(manifest.entries[1].part1, Answer::Integer(-1000));
+        assert_eq!(
+            manifest.entries[1].part2,
+            Answer::Grid(vec![String::from("# #"), String::from(" \"#")])
+        );
+
+        // Writing it out and reading it back gives the same manifest.
+        assert_eq!(Manifest::parse(&manifest.to_string()).unwrap(), manifest);
+    }
+
+    #[test]
+    fn test_bad_manifest() {
+        let err = Manifest::parse("[[answer]]\nyear = 2021\nday = 1\npart1 = x7\npart2 = 1").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(4), Some(9)));
+        let err = Manifest::parse("[[answer]]\nyear = 2021\nday = 1\npart1 = [\"a\" \"b\"]").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(4), Some(14)));
+        let err = Manifest::parse("[[answer]]\nyear = 2021\nday = 1\npart1 = 2").unwrap_err();
+        assert_eq!(err.line, Some(1));
+        let err = Manifest::parse("[[answer]]\nday = 1\npart1 = 2\npart2 = 3").unwrap_err();
+        assert_eq!(err.reason, "each answer needs a year, day, part1 and part2");
+        assert!(Manifest::parse("day = 1").is_err());
+        assert!(Manifest::parse("[[answer]]\nweek = 1").is_err());
+    }
+
+    #[test]
+    fn test_answers() {
+        let answers = Answers::parse("part1 = 40  # lowest risk\n\npart2 = [\"# #\", \" # \"]\n").unwrap();
+        assert_eq!(answers.part1, Some(Answer::Integer(40)));
+        assert_eq!(answers.part2, Some(Answer::Grid(vec![String::from("# #"), String::from(" # ")])));
+        assert_eq!(Answers::parse("part2 = 315").unwrap().part1, None);
+        let err = Answers::parse("part1 = 40\npart3 = 1").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(2), Some(1)));
+        assert!(Answers::parse("40").is_err());
+    }
+
+    #[test]
+    fn test_record() {
+        let mut manifest = Manifest::parse(MANIFEST).unwrap();
+        let mut expected = manifest.entries[0].clone();
+        expected.part2 = Answer::Integer(6);
+        manifest.record(expected);
+        expected = manifest.entries[0].clone();
+        expected.day = 2;
+        manifest.record(expected.clone());
+        expected.year = 2022;
+        manifest.record(expected);
+        let days = manifest.entries.iter().map(|e| (e.year, e.day)).collect::<Vec<(usize, usize)>>();
+        assert_eq!(days, vec![(2021, 1), (2021, 2), (2022, 2), (2022, 13)]);
+        assert_eq!(manifest.entries[0].part2, Answer::Integer(6));
+    }
+}
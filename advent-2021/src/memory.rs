@@ -0,0 +1,185 @@
+use std::fmt;
+
+// The memory a day used, from the counting allocator built with the alloc-stats feature.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Memory {
+    pub allocations: u64,
+    // Every byte asked for, including each time a Vec or String grows.
+    pub allocated_bytes: u64,
+    // The most held at once, beyond what was already held when the day started.
+    pub peak_bytes: u64,
+}
+
+impl fmt::Display for Memory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} allocations, {} allocated, {} peak",
+            self.allocations,
+            pretty_bytes(self.allocated_bytes),
+            pretty_bytes(self.peak_bytes)
+        )
+    }
+}
+
+pub fn pretty_bytes(bytes: u64) -> String {
+    if bytes > 1 << 30 {
+        format!("{}GiB", bytes >> 30)
+    } else if bytes > 1 << 20 {
+        format!("{}MiB", bytes >> 20)
+    } else if bytes > 1 << 10 {
+        format!("{}KiB", bytes >> 10)
+    } else {
+        format!("{}B", bytes)
+    }
+}
+
+// The allocator that keeps the counts. It's up to the binary to install it, as main does.
+#[cfg(feature = "alloc-stats")]
+pub use counting::Counting;
+
+// Call `f`, along with the memory it used, or None if allocations aren't being counted. Only
+// allocations made on the current thread are counted, and only once Counting is installed.
+#[cfg(feature = "alloc-stats")]
+pub fn measure<T, F: FnOnce() -> T>(f: F) -> (T, Option<Memory>) {
+    let before = counting::now();
+    counting::reset_peak();
+    let result = f();
+    let after = counting::now();
+    let memory = Memory {
+        allocations: after.allocations - before.allocations,
+        allocated_bytes: after.allocated - before.allocated,
+        peak_bytes: after.peak.saturating_sub(before.live).max(0) as u64,
+    };
+    (result, Some(memory))
+}
+
+#[cfg(not(feature = "alloc-stats"))]
+pub fn measure<T, F: FnOnce() -> T>(f: F) -> (T, Option<Memory>) {
+    (f(), None)
+}
+
+// Counts are kept per thread, so days solved side by side by the parallel runner don't
+// count each other's allocations.
+#[cfg(feature = "alloc-stats")]
+mod counting {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+
+    #[derive(Clone, Copy, Default)]
+    pub struct Counts {
+        pub allocations: u64,
+        pub allocated: u64,
+        // Memory freed on another thread than it was allocated on can take this below zero.
+        pub live: i64,
+        pub peak: i64,
+    }
+
+    // Nothing here may allocate, so the counts are plain Cells with a const initialiser.
+    thread_local! {
+        static COUNTS: Cell<Counts> = const {
+            Cell::new(Counts {
+                allocations: 0,
+                allocated: 0,
+                live: 0,
+                peak: 0,
+            })
+        };
+    }
+
+    pub fn now() -> Counts {
+        COUNTS.with(Cell::get)
+    }
+
+    pub fn reset_peak() {
+        COUNTS.with(|c| c.set(Counts { peak: c.get().live, ..c.get() }));
+    }
+
+    fn record(allocated: usize, freed: usize) {
+        // Once a thread's storage has gone, its last few frees are let through uncounted.
+        let _ = COUNTS.try_with(|c| {
+            let mut counts = c.get();
+            if allocated > 0 {
+                counts.allocations += 1;
+                counts.allocated += allocated as u64;
+            }
+            counts.live += allocated as i64 - freed as i64;
+            counts.peak = counts.peak.max(counts.live);
+            c.set(counts);
+        });
+    }
+
+    // The System allocator, keeping counts as it goes.
+    pub struct Counting;
+
+    unsafe impl GlobalAlloc for Counting {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc(layout);
+            if !ptr.is_null() {
+                record(layout.size(), 0);
+            }
+            ptr
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc_zeroed(layout);
+            if !ptr.is_null() {
+                record(layout.size(), 0);
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout);
+            record(0, layout.size());
+        }
+
+        // Growing counts as a new allocation of the whole new size.
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            let new = System.realloc(ptr, layout, new_size);
+            if !new.is_null() {
+                record(new_size, layout.size());
+            }
+            new
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let memory = Memory {
+            allocations: 12,
+            allocated_bytes: 3 << 20,
+            peak_bytes: 1500,
+        };
+        assert_eq!(memory.to_string(), "12 allocations, 3MiB allocated, 1KiB peak");
+        assert_eq!(pretty_bytes(0), "0B");
+    }
+
+    // Installed for the tests the same way main installs it.
+    #[cfg(feature = "alloc-stats")]
+    #[global_allocator]
+    static ALLOCATOR: Counting = Counting;
+
+    #[cfg(feature = "alloc-stats")]
+    #[test]
+    fn test_measure() {
+        let held = vec![0u8; 4096];
+        let (_, memory) = measure(|| {
+            let a = vec![0u8; 1000];
+            drop(a);
+            let b = Vec::<u64>::with_capacity(100);
+            drop(b);
+        });
+        let memory = memory.unwrap();
+        assert_eq!(memory.allocations, 2);
+        assert_eq!(memory.allocated_bytes, 1800);
+        // Only one of them was held at a time, and what was already held doesn't count.
+        assert_eq!(memory.peak_bytes, 1000);
+        drop(held);
+    }
+}
@@ -0,0 +1,483 @@
+use crate::{BenchConfig, CompareConfig, Format, InputSource, Level, Tracing, DAYS_PER_YEAR};
+use std::path::PathBuf;
+use std::time::Duration;
+
+// What the runner has been asked to do.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Command {
+    // Solve each day once.
+    #[default]
+    Run,
+    // Solve each day many times, and report statistics on the timings.
+    Bench,
+    // Check each day against the answers in a manifest, or with --write record them there.
+    Verify,
+    // Add the source and input files for a new day.
+    NewDay,
+    // Browse and run the days full screen. Needs the color feature.
+    Tui,
+    // Play back a day's working, from its Visualize frames. Needs the color feature.
+    Play,
+    // Check the latest run in the history for days that got slower.
+    Compare,
+    // Solve each day and write up the answers and timings as Markdown or HTML.
+    Report,
+    // Solve each day on every one of its inputs in a directory, as a table of day by input.
+    Batch,
+    // Download the input for each day, unless it's already been downloaded. Needs the fetch feature.
+    Fetch,
+}
+
+// Where fetch downloads inputs from and keeps them. Anything not given has a default in fetch.rs.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FetchConfig {
+    pub base_url: Option<String>,
+    // Where to read the session token from, if it's not in AOC_SESSION.
+    pub session_file: Option<PathBuf>,
+    pub cache_dir: Option<PathBuf>,
+}
+
+// Command line options shared by the runners.
+#[derive(Debug, Default, PartialEq)]
+pub struct Options {
+    pub command: Command,
+    // The year to run. None runs the latest one there are solutions for.
+    pub year: Option<usize>,
+    // The day to run. None (or day 0) runs every day.
+    pub day: Option<usize>,
+    pub input: InputSource,
+    pub format: Format,
+    pub bench: BenchConfig,
+    // Solve the days on this many threads, with 0 meaning one per CPU. None solves them in turn.
+    pub jobs: Option<usize>,
+    // The manifest used by verify, if not the default.
+    pub manifest: Option<PathBuf>,
+    // Have verify write the current answers to the manifest, rather than checking them.
+    pub write: bool,
+    // Print the metrics each day counted, such as states explored, along with its answers.
+    pub stats: bool,
+    // The trace messages to print, from -v and --trace.
+    pub tracing: Tracing,
+    // How long each day may run before it's marked as timed out and the next one started.
+    pub timeout: Option<Duration>,
+    // Solve the day again whenever its input changes.
+    pub watch: bool,
+    // Add each day's timings to the history, to compare later.
+    pub record: bool,
+    // The history file used by --record and compare, if not the default.
+    pub history: Option<PathBuf>,
+    // The machine the timings are recorded or compared for, if not this one.
+    pub machine: Option<String>,
+    pub compare: CompareConfig,
+    // Write the report as a standalone HTML page, rather than Markdown.
+    pub html: bool,
+    // Where to write the report, rather than stdout.
+    pub output: Option<PathBuf>,
+    // A Markdown file whose report section should be brought up to date.
+    pub readme: Option<PathBuf>,
+    // The directory of inputs used by batch, laid out as dayN/<name>.dat.
+    pub batch_dir: Option<PathBuf>,
+    pub fetch: FetchConfig,
+}
+
+impl Options {
+    // Parse from the program arguments, with the program name already skipped.
+    pub fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Self, String> {
+        let mut options = Options::default();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--input" => {
+                    let path = args.next().ok_or("--input needs a file, or - for stdin")?;
+                    options.input = if path == "-" {
+                        InputSource::Stdin
+                    } else {
+                        InputSource::File(PathBuf::from(path))
+                    };
+                }
+                "--input-dir" => {
+                    let dir = args.next().ok_or("--input-dir needs a directory")?;
+                    options.input = InputSource::Dir(PathBuf::from(dir));
+                }
+                "--format" => {
+                    let format = args.next().ok_or("--format needs json, csv or text")?;
+                    options.format = format.parse()?;
+                }
+                "--year" => options.year = Some(count(args.next(), "--year")?),
+                "--jobs" | "-j" => options.jobs = Some(count(args.next(), "--jobs")?),
+                "bench" => options.command = Command::Bench,
+                "verify" => options.command = Command::Verify,
+                "new-day" => options.command = Command::NewDay,
+                "tui" => options.command = Command::Tui,
+                "play" => options.command = Command::Play,
+                "compare" => options.command = Command::Compare,
+                "report" => options.command = Command::Report,
+                "fetch" => options.command = Command::Fetch,
+                "--base-url" => options.fetch.base_url = Some(args.next().ok_or("--base-url needs a URL")?),
+                "--session-file" => {
+                    let path = args.next().ok_or("--session-file needs a file")?;
+                    options.fetch.session_file = Some(PathBuf::from(path));
+                }
+                "--cache-dir" => {
+                    let dir = args.next().ok_or("--cache-dir needs a directory")?;
+                    options.fetch.cache_dir = Some(PathBuf::from(dir));
+                }
+                "batch" => {
+                    let dir = args.next().ok_or("batch needs a directory of inputs")?;
+                    options.command = Command::Batch;
+                    options.batch_dir = Some(PathBuf::from(dir));
+                }
+                "--html" => options.html = true,
+                "--output" => {
+                    let path = args.next().ok_or("--output needs a file")?;
+                    options.output = Some(PathBuf::from(path));
+                }
+                "--readme" => {
+                    let path = args.next().ok_or("--readme needs a file")?;
+                    options.readme = Some(PathBuf::from(path));
+                }
+                "--manifest" => {
+                    let path = args.next().ok_or("--manifest needs a file")?;
+                    options.manifest = Some(PathBuf::from(path));
+                }
+                "--write" => options.write = true,
+                "--record" => options.record = true,
+                "--history" => {
+                    let path = args.next().ok_or("--history needs a file")?;
+                    options.history = Some(PathBuf::from(path));
+                }
+                "--machine" => {
+                    let label = args.next().ok_or("--machine needs a label")?;
+                    // The label is a field of each record in the history.
+                    if label.is_empty() || label.contains([',', '\n', '\r']) {
+                        return Err(String::from("--machine needs a label without commas"));
+                    }
+                    options.machine = Some(label);
+                }
+                "--baseline" => options.compare.baseline = Some(args.next().ok_or("--baseline needs a revision")?),
+                "--threshold" => options.compare.threshold = count(args.next(), "--threshold")?,
+                "--stats" => options.stats = true,
+                "--watch" => options.watch = true,
+                // Once for debug messages, twice for everything.
+                "-v" => options.tracing.level = Some(options.tracing.level.map_or(Level::Debug, |_| Level::Trace)),
+                "-vv" => options.tracing.level = Some(Level::Trace),
+                "--trace" => {
+                    let day = args.next().ok_or("--trace needs a day, such as day4")?;
+                    options.tracing.days.push(traced_day(&day)?);
+                }
+                "--runs" => options.bench.runs = count(args.next(), "--runs")?,
+                "--warmup" => options.bench.warmup = count(args.next(), "--warmup")?,
+                "--timeout" => {
+                    let millis = count(args.next(), "--timeout")?;
+                    options.timeout = Some(Duration::from_millis(millis as u64));
+                }
+                "--budget-ms" => {
+                    let millis = count(args.next(), "--budget-ms")?;
+                    options.bench.budget = Some(Duration::from_millis(millis as u64));
+                }
+                _ => match arg.parse::<usize>() {
+                    Ok(0) => options.day = None,
+                    Ok(day) => options.day = Some(day),
+                    Err(_) => return Err(format!("Unrecognised argument {}", arg)),
+                },
+            }
+        }
+
+        // A single input file (or stdin) only makes sense for a single day.
+        if options.day.is_none()
+            && matches!(options.input, InputSource::File(_) | InputSource::Stdin)
+        {
+            return Err(String::from("--input needs a day to run"));
+        }
+
+        if options.command == Command::NewDay && options.day.is_none() {
+            return Err(String::from("new-day needs the day to add"));
+        }
+
+        if options.command == Command::Play && options.day.is_none() {
+            return Err(String::from("play needs the day to show"));
+        }
+
+        // The keys come in on stdin, and one input file can't serve every day in the list.
+        if options.command == Command::Tui && matches!(options.input, InputSource::File(_) | InputSource::Stdin) {
+            return Err(String::from("tui reads input from --input-dir or the bundled files"));
+        }
+
+        // Trace messages would scribble over the screen.
+        if matches!(options.command, Command::Tui | Command::Play) && options.tracing != Tracing::default() {
+            return Err(String::from("-v and --trace can't be used full screen"));
+        }
+
+        if options.command == Command::Play && options.input == InputSource::Stdin {
+            return Err(String::from("play reads keys from stdin, so needs its input from a file"));
+        }
+
+        if options.write && options.command != Command::Verify {
+            return Err(String::from("--write is only used by verify"));
+        }
+
+        if options.fetch != FetchConfig::default() && options.command != Command::Fetch {
+            return Err(String::from("--base-url, --session-file and --cache-dir are only used by fetch"));
+        }
+
+        if options.command == Command::Batch && options.input != InputSource::Bundled {
+            return Err(String::from("batch reads every input from its directory"));
+        }
+
+        if options.timeout.is_some() && !matches!(options.command, Command::Run | Command::Tui | Command::Batch) {
+            return Err(String::from("--timeout is only used when solving days"));
+        }
+
+        if options.watch {
+            if options.command != Command::Run || options.day.is_none() {
+                return Err(String::from("--watch needs a day to solve"));
+            }
+            if options.input == InputSource::Stdin {
+                return Err(String::from("--watch needs input from a file, not stdin"));
+            }
+            // Each rerun is compared with the one before, which only text output has room for.
+            if options.format != Format::Text || options.record {
+                return Err(String::from("--watch needs text output, and can't be recorded"));
+            }
+        }
+
+        if options.record && options.command != Command::Run {
+            return Err(String::from("--record is only used when solving days"));
+        }
+
+        if (options.history.is_some() || options.machine.is_some())
+            && !(options.record || options.command == Command::Compare)
+        {
+            return Err(String::from("--history and --machine are only used with --record or compare"));
+        }
+
+        if options.compare != CompareConfig::default() && options.command != Command::Compare {
+            return Err(String::from("--baseline and --threshold are only used by compare"));
+        }
+
+        if (options.html || options.output.is_some() || options.readme.is_some()) && options.command != Command::Report {
+            return Err(String::from("--html, --output and --readme are only used by report"));
+        }
+
+        if options.html && options.readme.is_some() {
+            return Err(String::from("--readme needs a Markdown report"));
+        }
+
+        if options.stats && options.command != Command::Run {
+            return Err(String::from("--stats is only used when solving days"));
+        }
+
+        // JSON records always carry the metrics, and CSV has no column for them.
+        if options.stats && options.format == Format::Csv {
+            return Err(String::from("--stats needs text output"));
+        }
+
+        // Benchmarks running side by side would only slow each other down.
+        if options.command == Command::Bench && options.jobs.is_some() {
+            return Err(String::from("--jobs can't be used with bench"));
+        }
+
+        // Only a plain run solves its days side by side.
+        if options.jobs.is_some() && options.command != Command::Run {
+            return Err(String::from("--jobs is only used when running days"));
+        }
+
+        // Other commands print tables and summaries, which have no records to format.
+        if options.format != Format::Text && options.command != Command::Run {
+            return Err(String::from("--format is only used when running days"));
+        }
+
+        if options.bench.runs == 0 {
+            return Err(String::from("--runs must be at least 1"));
+        }
+
+        Ok(options)
+    }
+}
+
+fn count(arg: Option<String>, flag: &str) -> Result<usize, String> {
+    arg.and_then(|a| a.parse::<usize>().ok())
+        .ok_or(format!("{} needs a number", flag))
+}
+
+// A day to trace, given as dayN or just N.
+fn traced_day(arg: &str) -> Result<usize, String> {
+    arg.strip_prefix("day")
+        .unwrap_or(arg)
+        .parse::<usize>()
+        .ok()
+        .filter(|day| (1..=DAYS_PER_YEAR).contains(day))
+        .ok_or(format!("--trace needs a day from day1 to day{}, not {}", DAYS_PER_YEAR, arg))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        Options::parse(args.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn test_days() {
+        assert_eq!(parse(&[]).unwrap(), Options::default());
+        assert_eq!(parse(&["0"]).unwrap().day, None);
+        assert_eq!(parse(&["--year", "2021", "3"]).unwrap().year, Some(2021));
+        assert!(parse(&["--year"]).is_err());
+        assert!(parse(&["fifteen"]).is_err());
+    }
+
+    #[test]
+    fn test_input() {
+        let options = parse(&["15", "--input", "my15.dat"]).unwrap();
+        assert_eq!(options.day, Some(15));
+        assert_eq!(options.input, InputSource::File(PathBuf::from("my15.dat")));
+        assert_eq!(parse(&["--input", "-", "3"]).unwrap().input, InputSource::Stdin);
+        let options = parse(&["--input-dir", "inputs"]).unwrap();
+        assert_eq!(options.input, InputSource::Dir(PathBuf::from("inputs")));
+        assert!(parse(&["--input", "my15.dat"]).is_err());
+        assert!(parse(&["15", "--input"]).is_err());
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(parse(&["--format", "csv"]).unwrap().format, Format::Csv);
+        assert!(parse(&["--format", "yaml"]).is_err());
+        for command in ["bench", "verify", "report"] {
+            assert_eq!(parse(&[command, "--format", "json"]).unwrap_err(), "--format is only used when running days");
+        }
+        assert!(parse(&["batch", "inputs", "--format", "csv"]).is_err());
+        assert!(parse(&["bench", "--format", "text"]).is_ok());
+    }
+
+    #[test]
+    fn test_bench() {
+        let options = parse(&["bench", "19", "--runs", "5", "--budget-ms", "2000"]).unwrap();
+        assert_eq!(options.command, Command::Bench);
+        assert_eq!(options.day, Some(19));
+        assert_eq!(options.bench.runs, 5);
+        assert_eq!(options.bench.budget, Some(Duration::from_millis(2000)));
+        assert!(parse(&["bench", "--runs", "0"]).is_err());
+        assert!(parse(&["bench", "--warmup", "lots"]).is_err());
+    }
+
+    #[test]
+    fn test_jobs() {
+        assert_eq!(parse(&["--jobs", "0"]).unwrap().jobs, Some(0));
+        assert!(parse(&["bench", "-j", "4"]).is_err());
+        for command in ["report", "verify", "tui"] {
+            assert_eq!(parse(&[command, "-j", "4"]).unwrap_err(), "--jobs is only used when running days");
+        }
+        assert!(parse(&["batch", "inputs", "--jobs", "2"]).is_err());
+    }
+
+    #[test]
+    fn test_verify() {
+        let options = parse(&["verify", "--manifest", "mine.toml", "--write"]).unwrap();
+        assert_eq!(options.command, Command::Verify);
+        assert_eq!(options.manifest, Some(PathBuf::from("mine.toml")));
+        assert!(options.write);
+        assert!(parse(&["--write"]).is_err());
+    }
+
+    #[test]
+    fn test_stats() {
+        assert!(parse(&["--stats", "23"]).unwrap().stats);
+        assert!(parse(&["bench", "--stats"]).is_err());
+        assert!(parse(&["--stats", "--format", "csv"]).is_err());
+    }
+
+    #[test]
+    fn test_tracing() {
+        assert_eq!(parse(&["-v"]).unwrap().tracing.level, Some(Level::Debug));
+        assert_eq!(parse(&["-v", "-v"]).unwrap().tracing.level, Some(Level::Trace));
+        let options = parse(&["--trace", "day4", "--trace", "22"]).unwrap();
+        assert_eq!(options.tracing, Tracing { level: None, days: vec![4, 22] });
+        assert!(parse(&["--trace", "day26"]).is_err());
+        assert!(parse(&["--trace"]).is_err());
+        assert!(parse(&["tui", "-v"]).is_err());
+    }
+
+    #[test]
+    fn test_tui_and_play() {
+        assert_eq!(parse(&["tui", "13"]).unwrap().command, Command::Tui);
+        assert!(parse(&["tui", "13", "--input", "-"]).is_err());
+        assert_eq!(parse(&["play", "11"]).unwrap().command, Command::Play);
+        assert!(parse(&["play"]).is_err());
+        assert!(parse(&["play", "11", "--input", "-"]).is_err());
+    }
+
+    #[test]
+    fn test_timeout() {
+        assert_eq!(parse(&["--timeout", "1500"]).unwrap().timeout, Some(Duration::from_millis(1500)));
+        assert!(parse(&["tui", "--timeout", "100"]).is_ok());
+        assert!(parse(&["bench", "--timeout", "100"]).is_err());
+        assert!(parse(&["--timeout", "soon"]).is_err());
+    }
+
+    #[test]
+    fn test_record() {
+        let options = parse(&["--record", "--machine", "ci", "--history", "times.csv"]).unwrap();
+        assert!(options.record);
+        assert_eq!(options.machine.as_deref(), Some("ci"));
+        assert_eq!(options.history, Some(PathBuf::from("times.csv")));
+        assert!(parse(&["bench", "--record"]).is_err());
+        assert!(parse(&["--machine", "ci"]).is_err());
+        assert!(parse(&["--record", "--machine", "a,b"]).is_err());
+    }
+
+    #[test]
+    fn test_compare() {
+        let options = parse(&["compare", "19", "--baseline", "abc123", "--threshold", "25"]).unwrap();
+        assert_eq!((options.command, options.day), (Command::Compare, Some(19)));
+        assert_eq!(options.compare.baseline.as_deref(), Some("abc123"));
+        assert_eq!(options.compare.threshold, 25);
+        assert!(parse(&["compare", "--machine", "ci"]).is_ok());
+        assert!(parse(&["--threshold", "25"]).is_err());
+    }
+
+    #[test]
+    fn test_watch() {
+        let options = parse(&["15", "--watch", "--input-dir", "inputs"]).unwrap();
+        assert!(options.watch);
+        assert!(parse(&["--watch"]).is_err());
+        assert!(parse(&["15", "--watch", "--input", "-"]).is_err());
+        assert!(parse(&["15", "--watch", "--format", "json"]).is_err());
+        assert!(parse(&["bench", "15", "--watch"]).is_err());
+    }
+
+    #[test]
+    fn test_batch() {
+        let options = parse(&["batch", "inputs", "15", "--timeout", "1000"]).unwrap();
+        assert_eq!((options.command, options.day), (Command::Batch, Some(15)));
+        assert_eq!(options.batch_dir, Some(PathBuf::from("inputs")));
+        assert!(parse(&["batch"]).is_err());
+        assert!(parse(&["batch", "inputs", "--input-dir", "mine"]).is_err());
+    }
+
+    #[test]
+    fn test_fetch() {
+        let options = parse(&["fetch", "25", "--base-url", "http://127.0.0.1:8000", "--cache-dir", "cache"]).unwrap();
+        assert_eq!((options.command, options.day), (Command::Fetch, Some(25)));
+        assert_eq!(options.fetch.base_url.as_deref(), Some("http://127.0.0.1:8000"));
+        assert_eq!(options.fetch.cache_dir, Some(PathBuf::from("cache")));
+        assert!(parse(&["--session-file", "token"]).is_err());
+    }
+
+    #[test]
+    fn test_report() {
+        let options = parse(&["report", "--html", "--output", "report.html"]).unwrap();
+        assert_eq!(options.command, Command::Report);
+        assert!(options.html);
+        assert_eq!(options.output, Some(PathBuf::from("report.html")));
+        assert_eq!(parse(&["report", "--readme", "../README.md"]).unwrap().readme, Some(PathBuf::from("../README.md")));
+        assert!(parse(&["report", "--html", "--readme", "../README.md"]).is_err());
+        assert!(parse(&["--html"]).is_err());
+    }
+
+    #[test]
+    fn test_new_day() {
+        let options = parse(&["new-day", "24"]).unwrap();
+        assert_eq!((options.command, options.day), (Command::NewDay, Some(24)));
+        assert!(parse(&["new-day"]).is_err());
+    }
+}
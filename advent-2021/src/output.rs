@@ -0,0 +1,220 @@
+use crate::{Answer, DayError, DayResult, Memory, Metrics};
+use std::str::FromStr;
+
+// How the runners print each day.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Format {
+    // Prose for people to read.
+    #[default]
+    Text,
+    // One JSON object per line.
+    Json,
+    // A header line, then one row per day.
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!("Unknown format {}, expected json, csv or text", s)),
+        }
+    }
+}
+
+// The columns of a CSV report, which are also the fields of each JSON record.
+// Only add to the end of this, so reports from different commits can still be compared.
+pub const CSV_HEADER: &str = "day,status,part1,part2,parse_us,part1_us,part2_us,total_us,error,year,allocations,allocated_bytes,peak_bytes";
+
+// A day as a single line of JSON. Failed days have null answers and timings, as does memory
+// when it isn't counted. After the CSV_HEADER fields come the day's metrics, as an object.
+pub fn json_record(year: usize, day: usize, result: &Result<DayResult, DayError>) -> String {
+    let fields = match result {
+        Ok(solution) => vec![
+            json_answer(&solution.part1),
+            json_answer(&solution.part2),
+            solution.timings.parse_us.to_string(),
+            solution.timings.part1_us.to_string(),
+            solution.timings.part2_us.to_string(),
+            solution.timings.total_us().to_string(),
+            String::from("null"),
+        ],
+        Err(e) => {
+            let mut fields = vec![String::from("null"); 6];
+            fields.push(json_string(&e.to_string()));
+            fields
+        }
+    };
+    let memory = memory_fields(result).map(|field| field.unwrap_or_else(|| String::from("null")));
+    let fields = fields.into_iter().chain([year.to_string()]).chain(memory);
+    let names = CSV_HEADER.split(',').skip(2);
+    let fields = names
+        .zip(fields)
+        .map(|(name, value)| format!("\"{}\":{}", name, value))
+        .collect::<Vec<String>>();
+    let metrics = match result {
+        Ok(solution) => json_metrics(&solution.metrics),
+        Err(_) => String::from("null"),
+    };
+    format!(
+        "{{\"day\":{},\"status\":\"{}\",{},\"metrics\":{}}}",
+        day,
+        status(result),
+        fields.join(","),
+        metrics
+    )
+}
+
+// A day as a row under CSV_HEADER. Failed days leave the answers and timings empty.
+pub fn csv_record(year: usize, day: usize, result: &Result<DayResult, DayError>) -> String {
+    let fields = match result {
+        Ok(solution) => vec![
+            csv_field(&solution.part1.to_string()),
+            csv_field(&solution.part2.to_string()),
+            solution.timings.parse_us.to_string(),
+            solution.timings.part1_us.to_string(),
+            solution.timings.part2_us.to_string(),
+            solution.timings.total_us().to_string(),
+            String::new(),
+        ],
+        Err(e) => {
+            let mut fields = vec![String::new(); 6];
+            fields.push(csv_field(&e.to_string()));
+            fields
+        }
+    };
+    let memory = memory_fields(result).map(Option::unwrap_or_default);
+    format!("{},{},{},{},{}", day, status(result), fields.join(","), year, memory.join(","))
+}
+
+// The allocations, bytes allocated and peak bytes, if they were counted.
+fn memory_fields(result: &Result<DayResult, DayError>) -> [Option<String>; 3] {
+    match result.as_ref().ok().and_then(|solution| solution.memory) {
+        Some(Memory {
+            allocations,
+            allocated_bytes,
+            peak_bytes,
+        }) => [allocations, allocated_bytes, peak_bytes].map(|n| Some(n.to_string())),
+        None => [None, None, None],
+    }
+}
+
+fn status(result: &Result<DayResult, DayError>) -> &'static str {
+    match result {
+        Ok(_) => "ok",
+        Err(e) if e.timed_out => "timeout",
+        Err(_) => "error",
+    }
+}
+
+// Integers are numbers, text is a string and a grid is an array of its rows.
+fn json_answer(answer: &Answer) -> String {
+    match answer {
+        Answer::Integer(value) => value.to_string(),
+        Answer::Text(text) => json_string(text),
+        Answer::Grid(rows) => format!(
+            "[{}]",
+            rows.iter().map(|r| json_string(r)).collect::<Vec<String>>().join(",")
+        ),
+    }
+}
+
+fn json_metrics(metrics: &Metrics) -> String {
+    let pairs = metrics
+        .iter()
+        .map(|(name, value)| format!("{}:{}", json_string(name), value))
+        .collect::<Vec<String>>();
+    format!("{{{}}}", pairs.join(","))
+}
+
+fn json_string(s: &str) -> String {
+    let mut escaped = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+// Quote a field if it holds anything that would break up the row.
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        String::from(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Timings;
+
+    fn solved() -> Result<DayResult, DayError> {
+        let mut metrics = Metrics::default();
+        metrics.set("part2.cache_hits", 7);
+        Ok(DayResult {
+            part1: Answer::Integer(-42),
+            part2: Answer::Grid(vec![String::from("# #"), String::from(" \"#")]),
+            description: String::new(),
+            timings: Timings {
+                parse_us: 1,
+                part1_us: 2,
+                part2_us: 3,
+            },
+            metrics,
+            memory: None,
+        })
+    }
+
+    #[test]
+    fn test_records() {
+        assert_eq!(
+            json_record(2021, 13, &solved()),
+            "{\"day\":13,\"status\":\"ok\",\"part1\":-42,\"part2\":[\"# #\",\" \\\"#\"],\
+             \"parse_us\":1,\"part1_us\":2,\"part2_us\":3,\"total_us\":6,\"error\":null,\"year\":2021,\
+             \"allocations\":null,\"allocated_bytes\":null,\"peak_bytes\":null,\"metrics\":{\"part2.cache_hits\":7}}"
+        );
+        assert_eq!(csv_record(2021, 13, &solved()), "13,ok,-42,\"# #\n \"\"#\",1,2,3,6,,2021,,,");
+
+        let failed = Err(DayError::at(3, 4, "could not parse 'x'").for_day(5));
+        assert_eq!(
+            json_record(2021, 5, &failed),
+            "{\"day\":5,\"status\":\"error\",\"part1\":null,\"part2\":null,\"parse_us\":null,\
+             \"part1_us\":null,\"part2_us\":null,\"total_us\":null,\
+             \"error\":\"Day 5 (line 3, column 4): could not parse 'x'\",\"year\":2021,\
+             \"allocations\":null,\"allocated_bytes\":null,\"peak_bytes\":null,\"metrics\":null}"
+        );
+        assert_eq!(
+            csv_record(2021, 5, &failed),
+            "5,error,,,,,,,\"Day 5 (line 3, column 4): could not parse 'x'\",2021,,,"
+        );
+
+        let mut measured = solved();
+        measured.as_mut().unwrap().memory = Some(Memory {
+            allocations: 4,
+            allocated_bytes: 100,
+            peak_bytes: 60,
+        });
+        assert!(csv_record(2021, 13, &measured).ends_with(",1,2,3,6,,2021,4,100,60"));
+        assert!(json_record(2021, 13, &measured).contains("\"allocations\":4,\"allocated_bytes\":100,\"peak_bytes\":60,"));
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!("json".parse::<Format>(), Ok(Format::Json));
+        assert!("xml".parse::<Format>().is_err());
+    }
+}
@@ -0,0 +1,130 @@
+use crate::{run_day_limited, DayError, DayResult, DaySolver, InputSource};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+// The number of threads to use when asked for `requested`, where 0 means one per CPU.
+pub fn worker_count(requested: usize) -> usize {
+    if requested > 0 {
+        requested
+    } else {
+        thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+    }
+}
+
+// Solve each (day number, solver) pair on a pool of `threads` workers, each day within
+// `timeout` if given. Results are handed to `report` on the calling thread in the order given,
+// each as soon as it and every day before it is done.
+pub fn run_parallel<F>(
+    days: &[(usize, &'static dyn DaySolver)],
+    source: &InputSource,
+    threads: usize,
+    timeout: Option<Duration>,
+    mut report: F,
+) where
+    F: FnMut(usize, Result<DayResult, DayError>),
+{
+    let threads = worker_count(threads).min(days.len()).max(1);
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            let sender = sender.clone();
+            let next = &next;
+            scope.spawn(move || loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                if i >= days.len() {
+                    break;
+                }
+                let (day, solver) = days[i];
+                // A worker only moves on once the day it gave up on has stopped, so there are
+                // never more than `threads` days running.
+                let result = run_day_limited(day, solver, source, timeout);
+                if sender.send((i, result)).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        // Hold back anything finished early until the days before it are reported.
+        let mut pending: Vec<Option<Result<DayResult, DayError>>> = days.iter().map(|_| None).collect();
+        let mut reported = 0;
+        for (i, result) in receiver {
+            pending[i] = Some(result);
+            while let Some(result) = pending.get_mut(reported).and_then(|r| r.take()) {
+                report(days[reported].0, result);
+                reported += 1;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PartResult, Solution};
+
+    // Sleeps for the given number of milliseconds, so later days can finish first.
+    struct Sleepy(u64);
+
+    impl Solution for Sleepy {
+        type Parsed = ();
+
+        fn bundled_input(&self) -> &'static str {
+            ""
+        }
+
+        fn parse(&self, _: &str) -> Result<Self::Parsed, DayError> {
+            thread::sleep(Duration::from_millis(self.0));
+            Ok(())
+        }
+
+        fn part1(&self, _: &Self::Parsed) -> Result<PartResult, DayError> {
+            Ok(PartResult::new(self.0, ""))
+        }
+
+        fn part2(&self, _: &Self::Parsed) -> Result<PartResult, DayError> {
+            if self.0 == 0 {
+                return Err(DayError::new("no sleep"));
+            }
+            Ok(PartResult::new(0, ""))
+        }
+    }
+
+    #[test]
+    fn test_day_order() {
+        static SOLVERS: [Sleepy; 6] = [Sleepy(40), Sleepy(0), Sleepy(20), Sleepy(5), Sleepy(30), Sleepy(1)];
+        // Day 4 is left out, and mustn't shift the days after it.
+        let days = [1, 2, 3, 5, 6, 7]
+            .iter()
+            .copied()
+            .zip(SOLVERS.iter().map(|s| s as &dyn DaySolver))
+            .collect::<Vec<(usize, &'static dyn DaySolver)>>();
+        let mut seen = vec![];
+        run_parallel(&days, &InputSource::Bundled, 3, None, |day, result| {
+            seen.push((day, result.map(|r| r.part1.to_string()).map_err(|e| e.day)));
+        });
+        assert_eq!(
+            seen,
+            vec![
+                (1, Ok(String::from("40"))),
+                (2, Err(Some(2))),
+                (3, Ok(String::from("20"))),
+                (5, Ok(String::from("5"))),
+                (6, Ok(String::from("30"))),
+                (7, Ok(String::from("1"))),
+            ]
+        );
+        assert!(worker_count(0) >= 1);
+
+        // Sleepy never checks in, so is given up on rather than stopped.
+        let mut statuses = vec![];
+        run_parallel(&days[..2], &InputSource::Bundled, 2, Some(Duration::from_millis(10)), |day, result| {
+            statuses.push((day, result.err().map(|e| e.timed_out)));
+        });
+        assert_eq!(statuses, vec![(1, Some(true)), (2, Some(false))]);
+    }
+}
@@ -0,0 +1,203 @@
+use crate::{column_of, offset_of, parse_at, DayError, Grid};
+use std::convert::TryInto;
+use std::str::FromStr;
+
+// A line of input, numbered from 1. The tokens handed to its methods must be slices of its
+// text, so that an error can give the column where the token starts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Line<'a> {
+    pub no: usize,
+    pub text: &'a str,
+}
+
+// A `key=from..to` range, such as `x=-4..46`. The bounds are kept in the order given.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeyRange<'a, T> {
+    pub key: &'a str,
+    pub from: T,
+    pub to: T,
+}
+
+// A run of non-blank lines, and the number of its first line in the whole input.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Section<'a> {
+    pub start: usize,
+    pub text: &'a str,
+}
+
+pub fn lines(input: &str) -> impl Iterator<Item = Line<'_>> {
+    numbered(input, 1)
+}
+
+// Split the input on blank lines. Runs of blank lines count as one, and leading or trailing
+// ones are dropped.
+pub fn sections(input: &str) -> Vec<Section<'_>> {
+    let mut sections = vec![];
+    // The first and last lines of the section being read.
+    let mut current: Option<(Line, Line)> = None;
+    for line in lines(input) {
+        if line.text.trim().is_empty() {
+            sections.extend(current.take().map(|(first, last)| Section::spanning(input, first, last)));
+        } else if let Some((_, last)) = current.as_mut() {
+            *last = line;
+        } else {
+            current = Some((line, line));
+        }
+    }
+    sections.extend(current.map(|(first, last)| Section::spanning(input, first, last)));
+    sections
+}
+
+fn numbered(input: &str, start: usize) -> impl Iterator<Item = Line<'_>> {
+    trim_blank_lines(input).lines().enumerate().map(move |(i, text)| Line { no: start + i, text })
+}
+
+// The input without any blank lines at its end, which editors and downloads tend to leave.
+// Every day's input and every line read goes through here, so no day has to allow for them.
+pub(crate) fn trim_blank_lines(input: &str) -> &str {
+    let content = input.trim_end().len();
+    match input[content..].find('\n') {
+        Some(newline) => &input[..content + newline + 1],
+        None => input,
+    }
+}
+
+impl<'a> Line<'a> {
+    pub fn error<S: Into<String>>(&self, token: &str, reason: S) -> DayError {
+        DayError::at(self.no, column_of(self.text, token), reason)
+    }
+
+    // An error saying what `token` should have looked like.
+    pub fn expected(&self, token: &str, what: &str) -> DayError {
+        self.error(token, format!("expected '{}', found '{}'", what, token))
+    }
+
+    pub fn parse<T: FromStr>(&self, token: &str) -> Result<T, DayError> {
+        parse_at(token, self.no, self.text)
+    }
+
+    // The parts of `token` either side of `separator`, where `what` describes the whole.
+    pub fn split_once(&self, token: &'a str, separator: &str, what: &str) -> Result<(&'a str, &'a str), DayError> {
+        token.split_once(separator).ok_or_else(|| self.expected(token, what))
+    }
+
+    pub fn strip_prefix(&self, token: &'a str, prefix: &str, what: &str) -> Result<&'a str, DayError> {
+        token.strip_prefix(prefix).ok_or_else(|| self.expected(token, what))
+    }
+
+    // Numbers separated by `separator`, such as `3,4,3,1,2`.
+    pub fn ints<T: FromStr>(&self, token: &'a str, separator: char) -> Result<Vec<T>, DayError> {
+        token.split(separator).map(|n| self.parse(n)).collect()
+    }
+
+    // Exactly N numbers separated by `separator`, such as the `x,y` of a point.
+    pub fn ints_n<T: FromStr, const N: usize>(&self, token: &'a str, separator: char) -> Result<[T; N], DayError> {
+        self.ints(token, separator)?.try_into().map_err(|_| {
+            self.error(token, format!("expected {} numbers separated by '{}', found '{}'", N, separator, token))
+        })
+    }
+
+    pub fn range<T: FromStr>(&self, token: &'a str) -> Result<KeyRange<'a, T>, DayError> {
+        let (key, bounds) = self.split_once(token, "=", "key=a..b")?;
+        let (from, to) = bounds.split_once("..").ok_or_else(|| self.expected(token, "key=a..b"))?;
+        Ok(KeyRange {
+            key,
+            from: self.parse(from)?,
+            to: self.parse(to)?,
+        })
+    }
+
+    // The characters of `token` along with their columns, if the token is part of the line.
+    pub fn chars(&self, token: &'a str) -> impl Iterator<Item = (Option<usize>, char)> + 'a {
+        let start = column_of(self.text, token);
+        token.chars().enumerate().map(move |(i, c)| (start.map(|start| start + i), c))
+    }
+}
+
+impl<'a> Section<'a> {
+    fn spanning(input: &'a str, first: Line, last: Line) -> Self {
+        let offset = |line: Line| offset_of(input, line.text).expect("lines are slices of the input");
+        let from = offset(first);
+        let to = offset(last) + last.text.len();
+        Self {
+            start: first.no,
+            text: &input[from..to],
+        }
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = Line<'a>> {
+        numbered(self.text, self.start)
+    }
+
+    // Read the section as a map with one character per cell, see Grid::parse.
+    pub fn grid<T, F>(&self, cell: F) -> Result<Grid<T>, DayError>
+    where
+        F: FnMut(char) -> Result<T, String>,
+    {
+        Grid::parse(self.text, cell).map_err(|e| self.within_input(e))
+    }
+
+    pub fn digits(&self) -> Result<Grid<u8>, DayError> {
+        Grid::parse_digits(self.text).map_err(|e| self.within_input(e))
+    }
+
+    // Errors from parsing the section number lines from its start.
+    fn within_input(&self, mut error: DayError) -> DayError {
+        error.line = error.line.map(|line| line + self.start - 1);
+        error
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sections() {
+        let input = "\nab\ncd\n\n\n12\n34\n";
+        let sections = sections(input);
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[0], Section { start: 2, text: "ab\ncd" });
+        assert_eq!(sections[1].lines().map(|l| l.no).collect::<Vec<usize>>(), vec![6, 7]);
+        assert_eq!(sections[1].digits().unwrap()[(1, 0)], 3);
+
+        let err = sections[0].digits().unwrap_err();
+        assert_eq!((err.line, err.column), (Some(2), Some(1)));
+        assert!(super::sections("\n\n").is_empty());
+        assert_eq!(super::sections("a\r\n\r\nb")[1], Section { start: 3, text: "b" });
+    }
+
+    #[test]
+    fn test_trailing_blank_lines() {
+        assert_eq!(trim_blank_lines("1\n2 \n\n \r\n"), "1\n2 \n");
+        assert_eq!(trim_blank_lines("1\r\n\r\n"), "1\r\n");
+        assert_eq!(trim_blank_lines("1 "), "1 ");
+        assert_eq!(lines("1\n2\n\n").map(|l| l.text).collect::<Vec<&str>>(), vec!["1", "2"]);
+        assert_eq!(sections("ab\ncd\n\n")[0].grid(Ok::<char, String>).unwrap().height(), 2);
+    }
+
+    #[test]
+    fn test_line() {
+        let line = lines("\n1,2 -> 3,x").nth(1).unwrap();
+        let (from, to) = line.split_once(line.text, " -> ", "a -> b").unwrap();
+        assert_eq!(line.ints_n::<i32, 2>(from, ','), Ok([1, 2]));
+        let err = line.ints::<i32>(to, ',').unwrap_err();
+        assert_eq!((err.line, err.column), (Some(2), Some(10)));
+        let err = line.ints_n::<i32, 3>(from, ',').unwrap_err();
+        assert_eq!((err.column, err.reason.as_str()), (Some(1), "expected 3 numbers separated by ',', found '1,2'"));
+        let err = line.split_once(to, "->", "a -> b").unwrap_err();
+        assert_eq!(err.column, Some(8));
+        assert_eq!(line.chars(to).nth(2), Some((Some(10), 'x')));
+    }
+
+    #[test]
+    fn test_range() {
+        let line = lines("on x=-4..46,y=3..z").next().unwrap();
+        let ranges = line.strip_prefix(line.text, "on ", "on ...").unwrap().split(',').collect::<Vec<&str>>();
+        assert_eq!(line.range(ranges[0]), Ok(KeyRange { key: "x", from: -4, to: 46 }));
+        assert_eq!(line.range::<i64>(ranges[1]).unwrap_err().column, Some(18));
+        let err = line.range::<i64>(&line.text[3..7]).unwrap_err();
+        assert_eq!((err.column, err.reason.as_str()), (Some(4), "expected 'key=a..b', found 'x=-4'"));
+        assert!(line.strip_prefix(line.text, "off ", "off ...").is_err());
+    }
+}
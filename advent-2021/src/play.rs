@@ -0,0 +1,175 @@
+use crate::tui::fit;
+use crate::Frame;
+use std::io::{self, Write};
+use std::thread;
+use std::time::{Duration, Instant};
+use termion::event::Key;
+use termion::input::TermRead;
+use termion::raw::IntoRawMode;
+use termion::screen::AlternateScreen;
+use termion::{clear, cursor, style};
+
+const HELP: &str = "space pause  left/right step  +/- speed  home restart  q quit";
+
+// How long each frame is shown for, from slowest to fastest.
+const SPEEDS_MS: [u64; 7] = [2000, 1000, 500, 200, 100, 50, 20];
+
+// How often to check for key presses while a frame is showing.
+const TICK: Duration = Duration::from_millis(10);
+
+// Where playback has got to.
+#[derive(Debug, PartialEq)]
+struct Player {
+    frame: usize,
+    frames: usize,
+    speed: usize,
+    paused: bool,
+}
+
+impl Player {
+    fn new(frames: usize) -> Self {
+        Self {
+            frame: 0,
+            frames,
+            speed: 3,
+            paused: false,
+        }
+    }
+
+    fn delay(&self) -> Duration {
+        Duration::from_millis(SPEEDS_MS[self.speed])
+    }
+
+    // Act on a key, returning false to quit.
+    fn handle(&mut self, key: Key) -> bool {
+        match key {
+            Key::Char('q') | Key::Esc | Key::Ctrl('c') => return false,
+            Key::Char(' ') => self.paused = !self.paused,
+            Key::Char('+') | Key::Char('=') | Key::Up => self.speed = (self.speed + 1).min(SPEEDS_MS.len() - 1),
+            Key::Char('-') | Key::Down => self.speed = self.speed.saturating_sub(1),
+            // Stepping by hand stops playback, so the frame stays put.
+            Key::Right | Key::Char('l') => {
+                self.paused = true;
+                self.advance();
+            }
+            Key::Left | Key::Char('h') => {
+                self.paused = true;
+                self.frame = self.frame.saturating_sub(1);
+            }
+            Key::Home => self.frame = 0,
+            Key::End => self.frame = self.frames - 1,
+            _ => (),
+        }
+        true
+    }
+
+    // Move on a frame, pausing on the last one.
+    fn advance(&mut self) {
+        if self.frame + 1 < self.frames {
+            self.frame += 1;
+        } else {
+            self.paused = true;
+        }
+    }
+
+    // The screen as lines of exactly `width` characters. Pictures too big for the screen are cut
+    // off at the right and bottom.
+    fn render(&self, frame: &Frame, width: usize, height: usize) -> Vec<String> {
+        let mut lines = vec![fit(&frame.caption, width), fit("", width)];
+        let picture_height = height.saturating_sub(4);
+        let mut picture = frame.picture.lines().take(picture_height).map(|l| fit(l, width)).collect::<Vec<String>>();
+        picture.resize(picture_height, fit("", width));
+        lines.extend(picture);
+
+        let state = if self.paused { "paused" } else { "playing" };
+        lines.push(fit(
+            &format!(
+                "Frame {} of {}, {} every {}ms",
+                self.frame + 1,
+                self.frames,
+                state,
+                SPEEDS_MS[self.speed]
+            ),
+            width,
+        ));
+        lines.push(format!("{}{}{}", style::Invert, fit(HELP, width), style::Reset));
+        lines
+    }
+
+    fn draw<W: Write>(&self, out: &mut W, frame: &Frame) -> io::Result<()> {
+        let (width, height) = termion::terminal_size()?;
+        write!(out, "{}{}", clear::All, cursor::Goto(1, 1))?;
+        // Raw mode needs the carriage return as well as the newline.
+        write!(out, "{}", self.render(frame, width as usize, height as usize).join("\r\n"))?;
+        out.flush()
+    }
+}
+
+// Play the frames back full screen until the user quits.
+pub fn play(frames: &[Frame]) -> io::Result<()> {
+    if frames.is_empty() {
+        return Ok(());
+    }
+    let mut player = Player::new(frames.len());
+    let mut screen = AlternateScreen::from(io::stdout().into_raw_mode()?);
+    let mut keys = termion::async_stdin().keys();
+    write!(screen, "{}", cursor::Hide)?;
+
+    let mut shown_at = Instant::now();
+    let mut redraw = true;
+    loop {
+        if redraw {
+            player.draw(&mut screen, &frames[player.frame])?;
+            redraw = false;
+        }
+        for key in keys.by_ref() {
+            if !player.handle(key?) {
+                write!(screen, "{}", cursor::Show)?;
+                return screen.flush();
+            }
+            redraw = true;
+        }
+        if !player.paused && shown_at.elapsed() >= player.delay() {
+            player.advance();
+            shown_at = Instant::now();
+            redraw = true;
+        }
+        thread::sleep(TICK);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keys() {
+        let mut player = Player::new(3);
+        player.handle(Key::Char('+'));
+        assert_eq!(player.delay(), Duration::from_millis(100));
+        player.handle(Key::Right);
+        assert_eq!((player.frame, player.paused), (1, true));
+        player.handle(Key::Char(' '));
+        player.advance();
+        player.advance();
+        assert_eq!((player.frame, player.paused), (2, true));
+        player.handle(Key::Home);
+        player.handle(Key::Left);
+        assert_eq!(player.frame, 0);
+        for _ in 0..10 {
+            player.handle(Key::Char('-'));
+        }
+        assert_eq!(player.delay(), Duration::from_millis(2000));
+        assert!(!player.handle(Key::Char('q')));
+    }
+
+    #[test]
+    fn test_render() {
+        let player = Player::new(2);
+        let frame = Frame::new("Step 1", "#.#.#\n.#.#.\n#.#.#");
+        let lines = player.render(&frame, 4, 6);
+        assert_eq!(lines.len(), 6);
+        assert_eq!(lines[..4], ["Step", "    ", "#.#.", ".#.#"]);
+        assert_eq!(lines[4], "Fram");
+    }
+}
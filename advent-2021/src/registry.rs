@@ -0,0 +1,199 @@
+use crate::{DayError, DaySolver};
+use std::collections::BTreeMap;
+
+// Every Advent of Code calendar runs from day 1 to day 25.
+pub const DAYS_PER_YEAR: usize = 25;
+
+// What each day module declares about itself, as its INFO.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DayInfo {
+    pub day: usize,
+    pub title: &'static str,
+}
+
+// Solvers are static, so a day can be handed to a thread that may outlive whoever started it.
+pub struct Registered {
+    pub info: DayInfo,
+    pub solver: &'static dyn DaySolver,
+}
+
+// What a year holds for one of its days.
+pub enum Entry {
+    Solved(Registered),
+    // Solved, but its feature was turned off for this build.
+    CompiledOut,
+    Missing,
+}
+
+// One year's solutions, found by day number. Days without a solution stay in the calendar
+// as Missing, so a gap never shifts the days after it.
+pub struct Year {
+    pub year: usize,
+    days: Vec<Entry>,
+}
+
+impl Year {
+    pub fn new(year: usize) -> Self {
+        Self {
+            year,
+            days: (0..DAYS_PER_YEAR).map(|_| Entry::Missing).collect(),
+        }
+    }
+
+    pub fn add(&mut self, info: DayInfo, solver: &'static dyn DaySolver) {
+        self.set(info.day, Entry::Solved(Registered { info, solver }));
+    }
+
+    pub fn compile_out(&mut self, day: usize) {
+        self.set(day, Entry::CompiledOut);
+    }
+
+    // Panics if the day isn't in the calendar or is added twice, as either is a slip in the
+    // registration code rather than anything a user can fix.
+    fn set(&mut self, day: usize, entry: Entry) {
+        assert!((1..=DAYS_PER_YEAR).contains(&day), "{} has no day {}", self.year, day);
+        let current = &mut self.days[day - 1];
+        assert!(matches!(current, Entry::Missing), "Day {} of {} is added twice", day, self.year);
+        *current = entry;
+    }
+
+    // None for a day outside the calendar.
+    pub fn entry(&self, day: usize) -> Option<&Entry> {
+        day.checked_sub(1).and_then(|i| self.days.get(i))
+    }
+
+    pub fn get(&self, day: usize) -> Result<&'static dyn DaySolver, DayError> {
+        match self.entry(day) {
+            Some(Entry::Solved(registered)) => Ok(registered.solver),
+            Some(Entry::CompiledOut) => Err(DayError::new(format!(
+                "compiled out of this build, turn on the \"day{}\" feature to run it",
+                day
+            ))
+            .for_day(day)),
+            Some(Entry::Missing) => Err(DayError::new(format!("no solution for {} yet", self.year)).for_day(day)),
+            None => Err(DayError::new(format!("not in the {} calendar", self.year)).for_day(day)),
+        }
+    }
+
+    pub fn title(&self, day: usize) -> Option<&'static str> {
+        match self.entry(day) {
+            Some(Entry::Solved(registered)) => Some(registered.info.title),
+            _ => None,
+        }
+    }
+
+    // The days with solutions, in order, with their day numbers.
+    pub fn solved(&self) -> Vec<(usize, &'static dyn DaySolver)> {
+        self.days
+            .iter()
+            .enumerate()
+            .filter_map(|(i, entry)| match entry {
+                Entry::Solved(registered) => Some((i + 1, registered.solver)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn compiled_out(&self) -> Vec<usize> {
+        (1..=DAYS_PER_YEAR)
+            .filter(|&day| matches!(self.entry(day), Some(Entry::CompiledOut)))
+            .collect()
+    }
+
+    pub fn missing(&self) -> Vec<usize> {
+        (1..=DAYS_PER_YEAR)
+            .filter(|&day| matches!(self.entry(day), Some(Entry::Missing)))
+            .collect()
+    }
+}
+
+// Every year the runners know about.
+#[derive(Default)]
+pub struct Registry {
+    years: BTreeMap<usize, Year>,
+}
+
+impl Registry {
+    pub fn add(&mut self, year: Year) {
+        assert!(!self.years.contains_key(&year.year), "{} is registered twice", year.year);
+        self.years.insert(year.year, year);
+    }
+
+    // The given year, or the latest one when none is asked for.
+    pub fn year(&self, year: Option<usize>) -> Result<&Year, String> {
+        let found = match year {
+            Some(year) => self.years.get(&year),
+            None => self.years.values().next_back(),
+        };
+        found.ok_or_else(|| match year {
+            Some(year) => format!("No solutions for {}, only for {}", year, self.describe_years()),
+            None => String::from("No years are registered"),
+        })
+    }
+
+    pub fn years(&self) -> Vec<usize> {
+        self.years.keys().copied().collect()
+    }
+
+    fn describe_years(&self) -> String {
+        self.years().iter().map(|y| y.to_string()).collect::<Vec<String>>().join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gaps_stay_in_place() {
+        let mut year = Year::new(2022);
+        year.compile_out(2);
+        year.compile_out(5);
+        assert_eq!(year.compiled_out(), vec![2, 5]);
+        assert_eq!(year.missing()[..4], [1, 3, 4, 6]);
+        assert_eq!(year.get(4).err().and_then(|e| e.day), Some(4));
+        assert!(year.get(5).err().is_some_and(|e| e.reason.contains("\"day5\" feature")));
+        assert!(year.get(0).is_err());
+        assert!(year.get(DAYS_PER_YEAR + 1).is_err());
+
+        // 2021 has a gap for every day not in register_days!, whichever are turned on.
+        let year = crate::year2021();
+        let mut registered = crate::registrations().iter().map(|(_, r)| r.info.day).collect::<Vec<usize>>();
+        registered.extend(crate::compiled_out());
+        let missing = (1..=DAYS_PER_YEAR).filter(|day| !registered.contains(day)).collect::<Vec<usize>>();
+        assert_eq!(year.missing(), missing);
+        assert_eq!(year.solved().len() + year.compiled_out().len(), registered.len());
+    }
+
+    #[test]
+    #[cfg(feature = "day1")]
+    fn test_sparse_year() {
+        let mut sparse = Year::new(2022);
+        let info = DayInfo { day: 3, title: "Borrowed" };
+        sparse.add(info, &crate::day1::Day {});
+        assert_eq!(sparse.title(3), Some("Borrowed"));
+        assert_eq!(sparse.solved()[0].0, 3);
+        assert_eq!(sparse.missing().len(), 24);
+        assert!(sparse.get(1).is_err());
+
+        sparse.compile_out(4);
+        assert_eq!(sparse.compiled_out(), vec![4]);
+        assert!(sparse.get(4).err().is_some_and(|e| e.reason.contains("\"day4\" feature")));
+        assert_eq!(sparse.missing().len(), 23);
+    }
+
+    #[test]
+    fn test_registry() {
+        let mut registry = Registry::default();
+        assert!(registry.year(None).is_err());
+        registry.add(Year::new(2022));
+        registry.add(Year::new(2021));
+        assert_eq!(registry.years(), vec![2021, 2022]);
+        assert_eq!(registry.year(None).unwrap().year, 2022);
+        assert_eq!(registry.year(Some(2021)).unwrap().year, 2021);
+        assert_eq!(
+            registry.year(Some(2015)).err(),
+            Some(String::from("No solutions for 2015, only for 2021, 2022"))
+        );
+    }
+}
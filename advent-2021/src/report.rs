@@ -0,0 +1,218 @@
+use crate::{pretty_duration, Answer, DayError, DayResult};
+use std::fmt::Write;
+
+// The lines around the part of a README that `report --readme` rewrites. Everything between
+// them is replaced, so hand-written notes belong outside.
+pub const REPORT_START: &str = "<!-- report:start -->";
+pub const REPORT_END: &str = "<!-- report:end -->";
+
+// One day's row in a report.
+pub struct ReportDay {
+    pub day: usize,
+    pub title: &'static str,
+    pub result: Result<DayResult, DayError>,
+}
+
+// The answers, timings and stats of a year's days, as Markdown or a standalone HTML page.
+pub struct Report {
+    pub year: usize,
+    // The git revision the days were solved with.
+    pub revision: String,
+    pub days: Vec<ReportDay>,
+}
+
+impl Report {
+    // A line on where the numbers came from, with the command marked up by `code`.
+    fn intro(&self, code: fn(&str) -> String) -> String {
+        let build = if cfg!(debug_assertions) { "debug" } else { "release" };
+        format!(
+            "Answers and timings for {} from a {} build of {}, written by {}.",
+            self.year,
+            build,
+            self.revision,
+            code("cargo run --release -- report")
+        )
+    }
+
+    pub fn markdown(&self) -> String {
+        let mut out = format!("{}\n\n", self.intro(|command| format!("`{}`", command)));
+        out.push_str("| Day | Title | Part 1 | Part 2 | Time | Stats |\n");
+        out.push_str("|---:|---|---|---|---:|---|\n");
+        // Pictures don't fit in a table cell, so go in code blocks after it.
+        let mut pictures = vec![];
+        for day in &self.days {
+            let mut cells = vec![day.day.to_string(), markdown_cell(day.title)];
+            match &day.result {
+                Ok(solution) => {
+                    for (part, answer) in [(1, &solution.part1), (2, &solution.part2)] {
+                        match answer {
+                            Answer::Grid(rows) => {
+                                pictures.push((day.day, part, rows));
+                                cells.push(String::from("see below"));
+                            }
+                            answer => cells.push(markdown_cell(&answer.to_string())),
+                        }
+                    }
+                    cells.push(pretty_duration(solution.timings.total_us() as f64));
+                    cells.push(markdown_cell(&stats(solution)));
+                }
+                Err(e) => {
+                    cells.push(markdown_cell(&format!("failed: {}", e.reason)));
+                    cells.extend(vec![String::new(); 3]);
+                }
+            }
+            let _ = writeln!(out, "| {} |", cells.join(" | "));
+        }
+        for (day, part, rows) in pictures {
+            let _ = write!(out, "\nDay {}, part {}:\n\n```text\n{}\n```\n", day, part, rows.join("\n"));
+        }
+        out
+    }
+
+    pub fn html(&self) -> String {
+        let mut out = String::new();
+        let _ = write!(
+            out,
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Advent of Code {}</title>\n{}</head>\n<body>\n",
+            self.year, STYLE
+        );
+        let _ = writeln!(out, "<h1>Advent of Code {}</h1>", self.year);
+        let _ = writeln!(out, "<p>{}</p>", self.intro(|command| format!("<code>{}</code>", html_escape(command))));
+        out.push_str("<table>\n<tr><th>Day</th><th>Title</th><th>Part 1</th><th>Part 2</th><th>Time</th><th>Stats</th></tr>\n");
+        for day in &self.days {
+            let mut cells = vec![
+                format!("<td class=\"number\">{}</td>", day.day),
+                format!("<td>{}</td>", html_escape(day.title)),
+            ];
+            match &day.result {
+                Ok(solution) => {
+                    for answer in [&solution.part1, &solution.part2] {
+                        cells.push(match answer {
+                            Answer::Grid(rows) => format!("<td><pre>{}</pre></td>", html_escape(&rows.join("\n"))),
+                            answer => format!("<td>{}</td>", html_escape(&answer.to_string())),
+                        });
+                    }
+                    cells.push(format!(
+                        "<td class=\"number\">{}</td>",
+                        pretty_duration(solution.timings.total_us() as f64)
+                    ));
+                    cells.push(format!("<td>{}</td>", html_escape(&stats(solution))));
+                }
+                Err(e) => cells.push(format!(
+                    "<td colspan=\"4\" class=\"error\">failed: {}</td>",
+                    html_escape(&e.reason)
+                )),
+            }
+            let _ = writeln!(out, "<tr>{}</tr>", cells.concat());
+        }
+        out.push_str("</table>\n</body>\n</html>\n");
+        out
+    }
+}
+
+const STYLE: &str = "<style>
+body { font-family: sans-serif; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #ccc; padding: 0.2em 0.6em; text-align: left; vertical-align: top; }
+td.number { text-align: right; }
+td.error { color: #b00; }
+pre { margin: 0; line-height: 1; }
+</style>
+";
+
+// What the day counted, and the memory it used if that was counted too.
+fn stats(solution: &DayResult) -> String {
+    let mut stats = solution.metrics.to_string();
+    if let Some(memory) = solution.memory {
+        if !stats.is_empty() {
+            stats.push_str(", ");
+        }
+        stats.push_str(&memory.to_string());
+    }
+    stats
+}
+
+// A table cell can't hold a line break or a bare pipe.
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// Replace whatever is between the report markers in `text` with `section`.
+pub fn update_section(text: &str, section: &str) -> Result<String, String> {
+    let missing = || format!("expected a section between {} and {}", REPORT_START, REPORT_END);
+    let start = text.find(REPORT_START).ok_or_else(missing)? + REPORT_START.len();
+    let end = start + text[start..].find(REPORT_END).ok_or_else(missing)?;
+    Ok(format!("{}\n\n{}\n{}", &text[..start], section.trim_end(), &text[end..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Metrics, Timings};
+
+    fn report() -> Report {
+        let mut metrics = Metrics::default();
+        metrics.set("part1.folds", 1);
+        let folded = DayResult {
+            part1: Answer::Integer(17),
+            part2: Answer::Grid(vec![String::from("█ █"), String::from(" █ ")]),
+            description: String::new(),
+            timings: Timings {
+                parse_us: 100,
+                part1_us: 20,
+                part2_us: 30,
+            },
+            metrics,
+            memory: None,
+        };
+        Report {
+            year: 2021,
+            revision: String::from("abc1234"),
+            days: vec![
+                ReportDay {
+                    day: 13,
+                    title: "Transparent Origami",
+                    result: Ok(folded),
+                },
+                ReportDay {
+                    day: 14,
+                    title: "Extended Polymerization",
+                    result: Err(DayError::new("a | b").for_day(14)),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_markdown() {
+        let markdown = report().markdown();
+        assert!(markdown.contains("| 13 | Transparent Origami | 17 | see below | 150.0us | part1.folds=1 |\n"));
+        assert!(markdown.contains("| 14 | Extended Polymerization | failed: a \\| b |  |  |  |\n"));
+        assert!(markdown.ends_with("\nDay 13, part 2:\n\n```text\n█ █\n █ \n```\n"));
+    }
+
+    #[test]
+    fn test_html() {
+        let html = report().html();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("of abc1234, written by <code>cargo run --release -- report</code>.</p>"));
+        assert!(html.contains("<td><pre>█ █\n █ </pre></td>"));
+        assert!(html.contains("<td colspan=\"4\" class=\"error\">failed: a | b</td>"));
+    }
+
+    #[test]
+    fn test_update_section() {
+        let readme = format!("# Notes\n{}\nold table\n{}\n## More notes\n", REPORT_START, REPORT_END);
+        let updated = update_section(&readme, "new table\n").unwrap();
+        assert_eq!(updated, format!("# Notes\n{}\n\nnew table\n{}\n## More notes\n", REPORT_START, REPORT_END));
+        assert_eq!(update_section(&updated, "new table").unwrap(), updated);
+        assert!(update_section("# Notes\n", "table").is_err());
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{crate_dir, InputSource};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+// How often --watch looks at the files it's watching.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// Looks for changes to a set of files by checking when each was last modified, and its size in
+// case that lands within the same tick of the clock. Files that don't exist yet are watched for
+// appearing.
+pub struct Poller {
+    files: Vec<(PathBuf, Option<Stamp>)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Stamp {
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+fn stamp(path: &Path) -> Option<Stamp> {
+    let metadata = fs::metadata(path).ok()?;
+    Some(Stamp {
+        modified: metadata.modified().ok(),
+        len: metadata.len(),
+    })
+}
+
+impl Poller {
+    pub fn new(paths: Vec<PathBuf>) -> Self {
+        Self {
+            files: paths.into_iter().map(|path| {
+                let stamp = stamp(&path);
+                (path, stamp)
+            }).collect(),
+        }
+    }
+
+    // The files that have changed since the last look.
+    pub fn changed(&mut self) -> Vec<PathBuf> {
+        let mut changed = vec![];
+        for (path, seen) in self.files.iter_mut() {
+            let now = stamp(path);
+            if now != *seen {
+                *seen = now;
+                changed.push(path.clone());
+            }
+        }
+        changed
+    }
+}
+
+// The file a day's input comes from, for --watch to look at. The bundled input is read from
+// src/data, which is where it's compiled in from. Stdin can't be watched.
+pub fn watched_input(day: usize, source: &InputSource) -> Option<PathBuf> {
+    match source {
+        InputSource::Bundled => Some(crate_dir().join("src").join("data").join(format!("day{}.dat", day))),
+        InputSource::File(path) => Some(path.clone()),
+        InputSource::Dir(dir) => Some(dir.join(format!("day{}.dat", day))),
+        InputSource::Stdin => None,
+    }
+}
+
+// Where a watched day should read its input from. Once compiled in, the bundled input can't
+// change, so it's read from its file in src/data instead.
+pub fn rerun_source(day: usize, source: &InputSource) -> InputSource {
+    match (source, watched_input(day, source)) {
+        (InputSource::Bundled, Some(path)) if path.is_file() => InputSource::File(path),
+        (source, _) => source.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_poller() {
+        let dir = std::env::temp_dir().join(format!("advent21-rerun-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("day15.dat");
+        fs::write(&input, "1163751742").unwrap();
+        let later = dir.join("day16.dat");
+
+        let mut poller = Poller::new(vec![input.clone(), later.clone()]);
+        assert!(poller.changed().is_empty());
+        fs::write(&input, "1163751742\n1381373672").unwrap();
+        assert_eq!(poller.changed(), vec![input.clone()]);
+        assert!(poller.changed().is_empty());
+        fs::write(&later, "D2FE28").unwrap();
+        assert_eq!(poller.changed(), vec![later]);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_watched_input() {
+        let dir = InputSource::Dir(PathBuf::from("inputs"));
+        assert_eq!(watched_input(15, &dir), Some(PathBuf::from("inputs/day15.dat")));
+        assert_eq!(rerun_source(15, &dir), dir);
+        assert_eq!(watched_input(15, &InputSource::Stdin), None);
+        let bundled = watched_input(15, &InputSource::Bundled).unwrap();
+        assert!(bundled.ends_with("src/data/day15.dat"));
+        assert_eq!(rerun_source(15, &InputSource::Bundled), InputSource::File(bundled));
+    }
+}
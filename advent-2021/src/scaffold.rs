@@ -0,0 +1,165 @@
+use crate::DAYS_PER_YEAR;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// The directory holding this crate's Cargo.toml, which new days are added under.
+pub fn crate_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+// Add day `day` to the crate in `crate_dir`: the module from src/template, empty input
+// files, its registration in src/lib.rs and its feature in Cargo.toml. Returns the files created.
+pub fn scaffold_day(crate_dir: &Path, day: usize) -> Result<Vec<PathBuf>, String> {
+    let src = crate_dir.join("src");
+    let module = src.join(format!("day{}.rs", day));
+    let input = src.join("data").join(format!("day{}.dat", day));
+    let test_input = src.join("data").join(format!("test_day{}.dat", day));
+    if let Some(existing) = [&module, &input, &test_input].iter().find(|p| p.exists()) {
+        return Err(format!("Day {} already exists: {}", day, existing.display()));
+    }
+
+    let read = |path: &Path| fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e));
+    let template = read(&src.join("template"))?;
+    let lib_path = src.join("lib.rs");
+    let lib = register(&read(&lib_path)?, day)?;
+    let manifest_path = crate_dir.join("Cargo.toml");
+    let manifest = add_feature(&read(&manifest_path)?, day)?;
+
+    let write = |path: &Path, contents: &str| fs::write(path, contents).map_err(|e| format!("{}: {}", path.display(), e));
+    let source = template
+        .replace("dayN", &format!("day{}", day))
+        .replace("day: N,", &format!("day: {},", day));
+    write(&module, &source)?;
+    write(&input, "")?;
+    write(&test_input, "")?;
+    write(&lib_path, &lib)?;
+    write(&manifest_path, &manifest)?;
+    Ok(vec![module, input, test_input])
+}
+
+// Add a day's module to the register_days! list in the source of lib.rs.
+fn register(lib: &str, day: usize) -> Result<String, String> {
+    if !(1..=DAYS_PER_YEAR).contains(&day) {
+        return Err(format!("There is no day {}, days run from 1 to {}", day, DAYS_PER_YEAR));
+    }
+    let mut lines = lib.lines().map(String::from).collect::<Vec<String>>();
+    let list = block(&lines, "register_days! {", "}").ok_or("Could not find the register_days! list")?;
+    // Each line is `#[cfg(feature = "dayN")] dayN,`.
+    let registered = |line: &str| day_number(line.split_whitespace().last()?.strip_suffix(',')?);
+    let line = format!("    #[cfg(feature = \"day{}\")] day{},", day, day);
+    insert_in_order(&mut lines, list, day, registered, line)?;
+    Ok(lines.join("\n") + "\n")
+}
+
+// Add the day's feature to Cargo.toml, and to the all-days group.
+fn add_feature(manifest: &str, day: usize) -> Result<String, String> {
+    let mut lines = manifest.lines().map(String::from).collect::<Vec<String>>();
+    let group = block(&lines, "all-days = [", "]").ok_or("Could not find the all-days feature")?;
+    let grouped = |line: &str| day_number(line.trim().strip_suffix(',')?.strip_prefix('"')?.strip_suffix('"')?);
+    insert_in_order(&mut lines, group, day, grouped, format!("    \"day{}\",", day))?;
+
+    let features = block(&lines, "[features]", "").ok_or("Could not find the [features] table")?;
+    let feature = |line: &str| day_number(line.strip_suffix(" = []")?);
+    insert_in_order(&mut lines, features, day, feature, format!("day{} = []", day))?;
+    Ok(lines.join("\n") + "\n")
+}
+
+// The lines after `open` and before the next `close` line (or the end), as a range of indexes.
+fn block(lines: &[String], open: &str, close: &str) -> Option<(usize, usize)> {
+    let start = lines.iter().position(|l| l.starts_with(open))? + 1;
+    let end = lines[start..]
+        .iter()
+        .position(|l| l == close)
+        .map_or(lines.len(), |n| start + n);
+    Some((start, end))
+}
+
+fn day_number(name: &str) -> Option<usize> {
+    name.strip_prefix("day")?.parse().ok()
+}
+
+// Put `line` among the lines for other days within `range`, keeping them in day order.
+fn insert_in_order<F>(lines: &mut Vec<String>, range: (usize, usize), day: usize, day_of: F, line: String) -> Result<(), String>
+where
+    F: Fn(&str) -> Option<usize>,
+{
+    let (start, end) = range;
+    let days = lines[start..end].iter().filter_map(|l| day_of(l)).collect::<Vec<usize>>();
+    if days.contains(&day) {
+        return Err(format!("Day {} is already registered", day));
+    }
+    // After the last earlier day, so the new line joins the run of days rather than
+    // landing after anything else in the block.
+    let at = (start..end)
+        .rev()
+        .find(|&i| day_of(&lines[i]).is_some_and(|d| d < day))
+        .map(|i| i + 1)
+        .or_else(|| (start..end).find(|&i| day_of(&lines[i]).is_some()))
+        .unwrap_or(end);
+    lines.insert(at, line);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIB: &str = "use std::time::Instant;\n\nregister_days! {\n    #[cfg(feature = \"day2\")] day2,\n    \
+                       #[cfg(feature = \"day20\")] day20,\n}\n\npub fn days() {}\n";
+
+    const MANIFEST: &str = "[package]\nname = \"advent\"\n\n[features]\ndefault = [\"all-days\"]\nall-days = [\n    \
+                            \"day2\",\n    \"day20\",\n]\nday2 = []\nday20 = []\n";
+
+    #[test]
+    fn test_register() {
+        let lib = register(LIB, 3).unwrap();
+        assert_eq!(
+            lib,
+            "use std::time::Instant;\n\nregister_days! {\n    #[cfg(feature = \"day2\")] day2,\n    \
+             #[cfg(feature = \"day3\")] day3,\n    #[cfg(feature = \"day20\")] day20,\n}\n\npub fn days() {}\n"
+        );
+        assert!(register(LIB, 1).unwrap().contains("{\n    #[cfg(feature = \"day1\")] day1,\n    #[cfg(feature = \"day2\")]"));
+        assert!(register(LIB, 21).unwrap().contains("day20,\n    #[cfg(feature = \"day21\")] day21,\n}"));
+        assert!(register(LIB, 2).is_err());
+        assert!(register(LIB, 26).is_err());
+        assert!(register("mod day1;\n", 2).is_err());
+    }
+
+    #[test]
+    fn test_add_feature() {
+        assert_eq!(
+            add_feature(MANIFEST, 3).unwrap(),
+            "[package]\nname = \"advent\"\n\n[features]\ndefault = [\"all-days\"]\nall-days = [\n    \
+             \"day2\",\n    \"day3\",\n    \"day20\",\n]\nday2 = []\nday3 = []\nday20 = []\n"
+        );
+        assert!(add_feature(MANIFEST, 21).unwrap().ends_with("day20 = []\nday21 = []\n"));
+        assert!(add_feature(MANIFEST, 20).is_err());
+        assert!(add_feature("[package]\n", 1).is_err());
+    }
+
+    #[test]
+    fn test_scaffold_day() {
+        let dir = std::env::temp_dir().join(format!("advent21-scaffold-{}", std::process::id()));
+        fs::create_dir_all(dir.join("src/data")).unwrap();
+        fs::write(dir.join("src/template"), "DayInfo { day: N, .. } include_str!(\"data/test_dayN.dat\")").unwrap();
+        fs::write(dir.join("src/lib.rs"), LIB).unwrap();
+        fs::write(dir.join("Cargo.toml"), MANIFEST).unwrap();
+
+        let created = scaffold_day(&dir, 3).unwrap();
+        assert_eq!(created.len(), 3);
+        assert_eq!(
+            fs::read_to_string(dir.join("src/day3.rs")).unwrap(),
+            "DayInfo { day: 3, .. } include_str!(\"data/test_day3.dat\")"
+        );
+        assert_eq!(fs::read_to_string(dir.join("src/data/test_day3.dat")).unwrap(), "");
+        assert!(fs::read_to_string(dir.join("src/lib.rs")).unwrap().contains(" day3,\n"));
+        assert!(fs::read_to_string(dir.join("Cargo.toml")).unwrap().contains("day3 = []"));
+
+        // A second attempt must leave the first alone.
+        fs::write(dir.join("src/day3.rs"), "solved").unwrap();
+        assert!(scaffold_day(&dir, 3).is_err());
+        assert_eq!(fs::read_to_string(dir.join("src/day3.rs")).unwrap(), "solved");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
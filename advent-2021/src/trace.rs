@@ -0,0 +1,89 @@
+use crate::DAYS_PER_YEAR;
+use std::fmt;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+// How much detail a trace message goes into. Turning on a level shows every level below it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    // A line or two per step of a solution.
+    Debug = 1,
+    // Everything, such as each move tried or a whole grid.
+    Trace = 2,
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Level::Debug => write!(f, "debug"),
+            Level::Trace => write!(f, "trace"),
+        }
+    }
+}
+
+// Which days to trace. Nothing is traced by default.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Tracing {
+    // The level for every day, from -v.
+    pub level: Option<Level>,
+    // Days traced at every level, from --trace.
+    pub days: Vec<usize>,
+}
+
+impl Tracing {
+    pub fn level_for(&self, day: usize) -> Option<Level> {
+        if self.days.contains(&day) {
+            Some(Level::Trace)
+        } else {
+            self.level
+        }
+    }
+
+    // Make this the tracing used by every day from now on.
+    pub fn install(&self) {
+        for (day, level) in LEVELS.iter().enumerate() {
+            level.store(self.level_for(day).map_or(0, |l| l as u8), Ordering::Relaxed);
+        }
+    }
+}
+
+// The installed level for each day, with 0 for off. Days are checked before anything is
+// formatted, so this has to be cheap enough to sit in a solver's inner loop.
+static LEVELS: [AtomicU8; DAYS_PER_YEAR + 1] = [const { AtomicU8::new(0) }; DAYS_PER_YEAR + 1];
+
+pub fn tracing(day: usize, level: Level) -> bool {
+    LEVELS.get(day).is_some_and(|l| l.load(Ordering::Relaxed) >= level as u8)
+}
+
+// Trace messages go to stderr, so they don't mix with the answers.
+pub fn trace_line(day: usize, level: Level, message: fmt::Arguments) {
+    eprintln!("[day{} {}] {}", day, level, message);
+}
+
+// Trace a message for a day, such as `trace!(Level::Debug, INFO.day, "{} lit", n)`. The
+// message is only formatted if the day is being traced at that level.
+#[macro_export]
+macro_rules! trace {
+    ($level:expr, $day:expr, $($message:tt)+) => {
+        if $crate::tracing($day, $level) {
+            $crate::trace_line($day, $level, format_args!($($message)+));
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_for() {
+        assert_eq!(Tracing::default().level_for(4), None);
+
+        let tracing = Tracing {
+            level: Some(Level::Debug),
+            days: vec![12],
+        };
+        assert_eq!(tracing.level_for(4), Some(Level::Debug));
+        assert_eq!(tracing.level_for(12), Some(Level::Trace));
+        assert!(Level::Debug < Level::Trace);
+    }
+}
@@ -0,0 +1,385 @@
+use crate::{pretty_duration, start_day, Answer, DayError, DayResult, InputSource, Progress, Running, Watch, Year, DAYS_PER_YEAR};
+use std::io::{self, Write};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::Duration;
+use termion::event::Key;
+use termion::input::TermRead;
+use termion::raw::IntoRawMode;
+use termion::screen::AlternateScreen;
+use termion::{clear, cursor, style};
+
+const HELP: &str = "up/down move  space select  enter/r run  s run selected  a run all  c cancel  q quit";
+
+// How often keys are looked at while a day runs.
+const KEY_POLL: Duration = Duration::from_millis(50);
+
+// What's known about a day so far.
+enum Outcome {
+    NotRun,
+    Solved(DayResult),
+    Failed(DayError),
+}
+
+struct Row {
+    day: usize,
+    title: &'static str,
+    solvable: bool,
+    selected: bool,
+    outcome: Outcome,
+}
+
+// What the main loop should do after a key press.
+#[derive(Debug, PartialEq)]
+enum Action {
+    Redraw,
+    Run(Vec<usize>),
+    Cancel,
+    Quit,
+}
+
+struct App<'a> {
+    year: &'a Year,
+    source: &'a InputSource,
+    rows: Vec<Row>,
+    cursor: usize,
+    status: String,
+    // How long each day may run for.
+    timeout: Option<Duration>,
+}
+
+impl<'a> App<'a> {
+    fn new(year: &'a Year, source: &'a InputSource) -> Self {
+        let rows = (1..=DAYS_PER_YEAR)
+            .map(|day| Row {
+                day,
+                title: year.title(day).unwrap_or(""),
+                solvable: year.get(day).is_ok(),
+                selected: false,
+                outcome: Outcome::NotRun,
+            })
+            .collect();
+        Self {
+            year,
+            source,
+            rows,
+            cursor: 0,
+            status: String::new(),
+            timeout: None,
+        }
+    }
+
+    fn handle(&mut self, key: Key) -> Action {
+        match key {
+            Key::Char('q') | Key::Esc | Key::Ctrl('c') => return Action::Quit,
+            Key::Up | Key::Char('k') => self.cursor = self.cursor.saturating_sub(1),
+            Key::Down | Key::Char('j') => self.cursor = (self.cursor + 1).min(self.rows.len() - 1),
+            Key::Home => self.cursor = 0,
+            Key::End => self.cursor = self.rows.len() - 1,
+            Key::Char(' ') => {
+                let row = &mut self.rows[self.cursor];
+                row.selected = row.solvable && !row.selected;
+            }
+            Key::Char('\n') | Key::Char('r') => {
+                let row = &self.rows[self.cursor];
+                if !row.solvable {
+                    self.status = format!("Day {} has no solution for {}", row.day, self.year.year);
+                    return Action::Redraw;
+                }
+                return Action::Run(vec![row.day]);
+            }
+            Key::Char('s') => {
+                let days = self.days(|row| row.selected);
+                if days.is_empty() {
+                    self.status = String::from("Nothing selected, use space to pick days");
+                    return Action::Redraw;
+                }
+                return Action::Run(days);
+            }
+            Key::Char('a') => return Action::Run(self.days(|_| true)),
+            Key::Char('c') => return Action::Cancel,
+            _ => (),
+        }
+        Action::Redraw
+    }
+
+    fn days<F: Fn(&Row) -> bool>(&self, wanted: F) -> Vec<usize> {
+        self.rows.iter().filter(|r| r.solvable && wanted(r)).map(|r| r.day).collect()
+    }
+
+    // The day's progress is sent to `progress`, to be shown by whoever's drawing the screen.
+    fn start_day(&self, day: usize, progress: Sender<Progress>) -> Result<Running, DayError> {
+        let solver = self.year.get(day)?;
+        let watch = Watch::new(self.timeout).on_progress(move |p| {
+            let _ = progress.send(p);
+        });
+        Ok(start_day(day, solver, self.source, watch))
+    }
+
+    fn finish_day(&mut self, day: usize, result: Result<DayResult, DayError>) {
+        self.rows[day - 1].outcome = match result {
+            Ok(result) => Outcome::Solved(result),
+            Err(e) => Outcome::Failed(e),
+        };
+    }
+
+    #[cfg(all(test, feature = "day13"))]
+    fn run_day(&mut self, day: usize) {
+        let result = self.start_day(day, mpsc::channel().0).and_then(Running::wait);
+        self.finish_day(day, result);
+    }
+
+    // The screen as lines of at most `width` characters, not counting styling.
+    fn render(&self, width: usize, height: usize) -> Vec<String> {
+        let mut lines = vec![
+            fit(&format!("Advent of Code {}", self.year.year), width),
+            fit("", width),
+        ];
+
+        // Leave room for the title, the header, the separator, a few lines of detail and the footer.
+        let visible = height.saturating_sub(10).clamp(1, self.rows.len());
+        let top = self.cursor.saturating_sub(visible - 1).min(self.rows.len() - visible);
+        let answer_width = width.saturating_sub(30) / 2;
+        lines.push(fit(
+            &format!(
+                "         Day  Status   {:<w$}  {:<w$}  {:>9}",
+                "Part 1",
+                "Part 2",
+                "Time",
+                w = answer_width
+            ),
+            width,
+        ));
+        for (i, row) in self.rows.iter().enumerate().skip(top).take(visible) {
+            let line = fit(&row_text(row, answer_width), width);
+            if i == self.cursor {
+                lines.push(format!("{}{}{}", style::Invert, line, style::Reset));
+            } else {
+                lines.push(line);
+            }
+        }
+        lines.push(fit(&"-".repeat(width), width));
+
+        let detail_height = height.saturating_sub(lines.len() + 2);
+        let row = &self.rows[self.cursor];
+        let detail = match &row.outcome {
+            _ if !row.solvable => format!("Day {} has no solution for {} yet.", row.day, self.year.year),
+            Outcome::NotRun => format!("Day {}: {}\n\nNot run yet. Press enter to run it.", row.day, row.title),
+            Outcome::Solved(result) => {
+                let t = &result.timings;
+                format!(
+                    "Day {}: {}\n{}\n\nparse {}, part 1 {}, part 2 {}",
+                    row.day,
+                    row.title,
+                    result.description,
+                    us(t.parse_us),
+                    us(t.part1_us),
+                    us(t.part2_us)
+                )
+            }
+            Outcome::Failed(e) => e.to_string(),
+        };
+        let mut detail = detail.lines().map(|l| fit(l, width)).collect::<Vec<String>>();
+        detail.resize(detail_height, fit("", width));
+        lines.extend(detail);
+
+        lines.push(fit(&self.status, width));
+        lines.push(fit(HELP, width));
+        lines
+    }
+
+    fn draw<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        let (width, height) = termion::terminal_size()?;
+        write!(out, "{}{}", clear::All, cursor::Goto(1, 1))?;
+        let lines = self.render(width as usize, height as usize);
+        // Raw mode needs the carriage return as well as the newline.
+        write!(out, "{}", lines.join("\r\n"))?;
+        out.flush()
+    }
+}
+
+fn row_text(row: &Row, answer_width: usize) -> String {
+    let mark = match (row.solvable, row.selected) {
+        (false, _) => "   ",
+        (true, true) => "[x]",
+        (true, false) => "[ ]",
+    };
+    let (status, part1, part2, time) = match &row.outcome {
+        _ if !row.solvable => ("missing", String::new(), String::new(), String::new()),
+        Outcome::NotRun => ("", String::new(), String::new(), String::new()),
+        Outcome::Solved(result) => (
+            "ok",
+            summary(&result.part1),
+            summary(&result.part2),
+            us(result.timings.total_us()),
+        ),
+        Outcome::Failed(e) if e.timed_out => ("timeout", String::new(), String::new(), String::new()),
+        Outcome::Failed(_) => ("error", String::new(), String::new(), String::new()),
+    };
+    format!(
+        "    {}  {:>3}  {:<7}  {:<w$}  {:<w$}  {:>9}",
+        mark,
+        row.day,
+        status,
+        fit(&part1, answer_width),
+        fit(&part2, answer_width),
+        time,
+        w = answer_width
+    )
+}
+
+// An answer on one line. Grids are left for the detail pane.
+fn summary(answer: &Answer) -> String {
+    match answer {
+        Answer::Grid(rows) => format!("({} row grid)", rows.len()),
+        answer => answer.to_string(),
+    }
+}
+
+fn us(micros: u128) -> String {
+    pretty_duration(micros as f64)
+}
+
+// Cut or pad `s` to exactly `width` characters.
+pub(crate) fn fit(s: &str, width: usize) -> String {
+    let mut fitted = s.chars().take(width).collect::<String>();
+    let len = fitted.chars().count();
+    fitted.extend(std::iter::repeat_n(' ', width - len));
+    fitted
+}
+
+// Take over the terminal until the user quits. `start` is the day the cursor begins on, and
+// days running past `timeout` are stopped.
+pub fn run_tui(year: &Year, source: &InputSource, start: Option<usize>, timeout: Option<Duration>) -> io::Result<()> {
+    let mut app = App::new(year, source);
+    app.timeout = timeout;
+    if let Some(day) = start {
+        app.cursor = day.clamp(1, DAYS_PER_YEAR) - 1;
+    }
+    let mut screen = AlternateScreen::from(io::stdout().into_raw_mode()?);
+    write!(screen, "{}", cursor::Hide)?;
+    app.draw(&mut screen)?;
+
+    // Keys are read on a thread of their own, so they can still be pressed while a day runs.
+    let (sender, keys) = mpsc::channel();
+    thread::spawn(move || {
+        for key in io::stdin().keys() {
+            if sender.send(key).is_err() {
+                break;
+            }
+        }
+    });
+
+    while let Ok(key) = keys.recv() {
+        match app.handle(key?) {
+            Action::Quit => break,
+            Action::Redraw | Action::Cancel => (),
+            Action::Run(days) => {
+                if !run_days(&mut app, &days, &keys, &mut screen)? {
+                    break;
+                }
+            }
+        }
+        app.draw(&mut screen)?;
+    }
+
+    write!(screen, "{}", cursor::Show)?;
+    screen.flush()
+}
+
+// Run each of `days` in turn, keeping an eye out for keys meanwhile. Cancelling stops the day
+// running and skips the rest. Gives false if the user quit. Days that report their progress
+// have it shown on the status line as they run.
+fn run_days<W: Write>(app: &mut App, days: &[usize], keys: &Receiver<io::Result<Key>>, screen: &mut W) -> io::Result<bool> {
+    for (i, &day) in days.iter().enumerate() {
+        app.status = format!("Running day {} ({} of {}), c to cancel", day, i + 1, days.len());
+        app.draw(screen)?;
+        let (progress, reports) = mpsc::channel();
+        let running = match app.start_day(day, progress) {
+            Ok(running) => running,
+            Err(e) => {
+                app.finish_day(day, Err(e));
+                continue;
+            }
+        };
+        let result = loop {
+            if let Some(result) = running.poll(KEY_POLL) {
+                break result;
+            }
+            if let Some(progress) = reports.try_iter().last() {
+                app.status = format!("Running day {} ({} of {}): {}, c to cancel", day, i + 1, days.len(), progress);
+                app.draw(screen)?;
+            }
+            let Ok(key) = keys.try_recv() else {
+                continue;
+            };
+            match app.handle(key?) {
+                Action::Quit => {
+                    running.cancel();
+                    return Ok(false);
+                }
+                Action::Cancel => {
+                    app.finish_day(day, Err(running.cancel()));
+                    app.status = format!("Cancelled day {}", day);
+                    return Ok(true);
+                }
+                Action::Redraw | Action::Run(_) => app.draw(screen)?,
+            }
+        };
+        app.finish_day(day, result);
+    }
+    let failed = days
+        .iter()
+        .filter(|&&day| matches!(app.rows[day - 1].outcome, Outcome::Failed(_)))
+        .count();
+    app.status = format!("Ran {} days, {} failed", days.len(), failed);
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(any(feature = "all-days", feature = "day13"))]
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "all-days")]
+    fn test_keys() {
+        let year = crate::year2021();
+        let mut app = App::new(&year, &InputSource::Bundled);
+        assert_eq!(app.handle(Key::Up), Action::Redraw);
+        assert_eq!(app.cursor, 0);
+        assert_eq!(app.handle(Key::Char('s')), Action::Redraw);
+        app.handle(Key::Char(' '));
+        app.handle(Key::Down);
+        app.handle(Key::Down);
+        app.handle(Key::Char(' '));
+        assert_eq!(app.handle(Key::Char('s')), Action::Run(vec![1, 3]));
+        assert_eq!(app.handle(Key::Char('\n')), Action::Run(vec![3]));
+        assert_eq!(app.handle(Key::Char('a')), Action::Run((1..=23).collect()));
+
+        // Day 24 is missing, so can't be picked or run.
+        app.handle(Key::End);
+        app.handle(Key::Up);
+        app.handle(Key::Char(' '));
+        assert!(!app.rows[23].selected);
+        assert_eq!(app.handle(Key::Char('c')), Action::Cancel);
+        assert_eq!(app.handle(Key::Char('r')), Action::Redraw);
+        assert_eq!(app.handle(Key::Char('q')), Action::Quit);
+    }
+
+    #[test]
+    #[cfg(feature = "day13")]
+    fn test_render() {
+        let year = crate::year2021();
+        let mut app = App::new(&year, &InputSource::Bundled);
+        app.cursor = 12;
+        app.run_day(13);
+        let lines = app.render(100, 50);
+        assert_eq!(lines.len(), 50);
+        assert!(lines.iter().any(|l| l.contains(" 13  ok       765 ") && l.contains("(6 row grid)")));
+        assert!(lines.iter().any(|l| l.contains(" 24  missing")));
+        // The whole plot is in the detail pane.
+        assert_eq!(lines.iter().filter(|l| l.starts_with('█')).count(), 6);
+        assert_eq!(lines.last().unwrap().trim_end(), HELP);
+    }
+}
@@ -0,0 +1,329 @@
+use crate::{run_day, DayError, DayResult, DaySolver, InputSource};
+use std::cell::RefCell;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Progress is handed on at most this often, however often solvers check in.
+const PROGRESS_EVERY: Duration = Duration::from_millis(100);
+
+// Shared between a runner and the day it's running, so the runner can ask it to stop.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+// How far a solver has got, such as 7 of 40 scanners aligned.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Progress {
+    pub done: u64,
+    // None if the solver can't tell how much work there is.
+    pub total: Option<u64>,
+    pub unit: &'static str,
+}
+
+impl Progress {
+    pub fn of(done: usize, total: usize, unit: &'static str) -> Self {
+        Self {
+            done: done as u64,
+            total: Some(total as u64),
+            unit,
+        }
+    }
+
+    pub fn count(done: u64, unit: &'static str) -> Self {
+        Self { done, total: None, unit }
+    }
+}
+
+impl fmt::Display for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.total {
+            Some(total) => write!(f, "{} of {} {}", self.done, total, self.unit),
+            None => write!(f, "{} {}", self.done, self.unit),
+        }
+    }
+}
+
+// What a runner is watching a day for: its time limit, being cancelled, and its progress.
+#[derive(Default)]
+pub struct Watch {
+    token: CancelToken,
+    limit: Option<Duration>,
+    progress: Option<Box<dyn FnMut(Progress) + Send>>,
+}
+
+// The watch over whatever's running on this thread, and when it started.
+struct Watching {
+    watch: Watch,
+    started: Instant,
+    reported: Instant,
+}
+
+thread_local! {
+    static WATCHING: RefCell<Option<Watching>> = const { RefCell::new(None) };
+}
+
+impl Watch {
+    pub fn new(limit: Option<Duration>) -> Self {
+        Self {
+            limit,
+            ..Self::default()
+        }
+    }
+
+    // Progress is reported from the thread the day runs on.
+    pub fn on_progress<F: FnMut(Progress) + Send + 'static>(mut self, progress: F) -> Self {
+        self.progress = Some(Box::new(progress));
+        self
+    }
+
+    // A token that stops the watched day at its next checkpoint.
+    pub fn token(&self) -> CancelToken {
+        self.token.clone()
+    }
+
+    // Call `f` with this watch over it. Only code on the current thread checks in.
+    pub fn run<T, F: FnOnce() -> T>(self, f: F) -> T {
+        let now = Instant::now();
+        let previous = WATCHING.with(|w| {
+            w.replace(Some(Watching {
+                watch: self,
+                started: now,
+                reported: now,
+            }))
+        });
+        let result = f();
+        WATCHING.with(|w| w.replace(previous));
+        result
+    }
+}
+
+// Called by solvers from their main loop. Passes on their progress, and fails with an error for
+// them to return if the day has been cancelled or run out of time. Does nothing if the day isn't
+// being watched.
+pub fn checkpoint(progress: Progress) -> Result<(), DayError> {
+    WATCHING.with(|w| match w.borrow_mut().as_mut() {
+        Some(watching) => watching.check(progress),
+        None => Ok(()),
+    })
+}
+
+impl Watching {
+    fn check(&mut self, progress: Progress) -> Result<(), DayError> {
+        if let Some(limit) = self.watch.limit {
+            if self.started.elapsed() > limit {
+                return Err(DayError::timed_out(limit));
+            }
+        }
+        if self.watch.token.is_cancelled() {
+            return Err(DayError::new("cancelled"));
+        }
+        if let Some(report) = self.watch.progress.as_mut() {
+            if self.reported.elapsed() >= PROGRESS_EVERY {
+                self.reported = Instant::now();
+                report(progress);
+            }
+        }
+        Ok(())
+    }
+}
+
+// A day being solved on a thread of its own under a watch.
+pub struct Running {
+    day: usize,
+    token: CancelToken,
+    limit: Option<Duration>,
+    started: Instant,
+    result: Receiver<Result<DayResult, DayError>>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+// Start solving a day. Its thread is left to finish on its own if the day is given up on, but a
+// day that checks in stops at its next checkpoint.
+pub fn start_day(day: usize, solver: &'static dyn DaySolver, source: &InputSource, watch: Watch) -> Running {
+    let (sender, result) = mpsc::channel();
+    let mut running = Running {
+        day,
+        token: watch.token(),
+        limit: watch.limit,
+        started: Instant::now(),
+        result,
+        thread: None,
+    };
+    let failed = sender.clone();
+    let source = source.clone();
+    let spawned = thread::Builder::new().name(format!("day{}", day)).spawn(move || {
+        let _ = sender.send(watch.run(|| run_day(day, solver, &source)));
+    });
+    match spawned {
+        Ok(thread) => running.thread = Some(thread),
+        Err(e) => {
+            let _ = failed.send(Err(DayError::new(format!("could not start a thread: {}", e)).for_day(day)));
+        }
+    }
+    running
+}
+
+impl Running {
+    // Wait up to `wait` for the day to finish, giving None if it's still going. A day that runs
+    // past its time limit is stopped and given up on.
+    pub fn poll(&self, wait: Duration) -> Option<Result<DayResult, DayError>> {
+        let wait = match self.limit {
+            Some(limit) => wait.min(limit.saturating_sub(self.started.elapsed())),
+            None => wait,
+        };
+        match self.result.recv_timeout(wait) {
+            Ok(result) => Some(result),
+            Err(RecvTimeoutError::Disconnected) => Some(Err(DayError::new("panicked").for_day(self.day))),
+            Err(RecvTimeoutError::Timeout) => match self.limit {
+                Some(limit) if self.started.elapsed() >= limit => {
+                    self.token.cancel();
+                    Some(Err(DayError::timed_out(limit).for_day(self.day)))
+                }
+                _ => None,
+            },
+        }
+    }
+
+    pub fn wait(self) -> Result<DayResult, DayError> {
+        self.outcome()
+    }
+
+    fn outcome(&self) -> Result<DayResult, DayError> {
+        loop {
+            if let Some(result) = self.poll(Duration::MAX) {
+                return result;
+            }
+        }
+    }
+
+    // Like `wait`, but also waits for a day that's given up on to reach a checkpoint or finish.
+    fn wait_for_thread(mut self) -> Result<DayResult, DayError> {
+        let result = self.outcome();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        result
+    }
+
+    // Stop waiting for the day, and have it stop at its next checkpoint.
+    pub fn cancel(self) -> DayError {
+        self.token.cancel();
+        DayError::new("cancelled").for_day(self.day)
+    }
+}
+
+// Solve a day under a watch, waiting until it's done or its time limit is up.
+pub fn run_day_watched(day: usize, solver: &'static dyn DaySolver, source: &InputSource, watch: Watch) -> Result<DayResult, DayError> {
+    start_day(day, solver, source, watch).wait()
+}
+
+// Solve a day within `limit` if given, for runners that go on to solve more days. Without a
+// limit the day is solved on the calling thread. With one it's solved on a thread of its own,
+// and a day given up on still has its thread waited for, so it isn't left running alongside
+// whatever comes next.
+pub fn run_day_limited(day: usize, solver: &'static dyn DaySolver, source: &InputSource, limit: Option<Duration>) -> Result<DayResult, DayError> {
+    match limit {
+        Some(_) => start_day(day, solver, source, Watch::new(limit)).wait_for_thread(),
+        None => panic::catch_unwind(AssertUnwindSafe(|| run_day(day, solver, source)))
+            .unwrap_or_else(|_| Err(DayError::new("panicked").for_day(day))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PartResult, Solution};
+    use std::sync::Mutex;
+
+    // Takes a second to parse, without ever checking in.
+    struct Stubborn;
+
+    impl Solution for Stubborn {
+        type Parsed = ();
+
+        fn bundled_input(&self) -> &'static str {
+            ""
+        }
+
+        fn parse(&self, _: &str) -> Result<Self::Parsed, DayError> {
+            thread::sleep(Duration::from_secs(1));
+            Ok(())
+        }
+
+        fn part1(&self, _: &Self::Parsed) -> Result<PartResult, DayError> {
+            Ok(PartResult::new(1, ""))
+        }
+
+        fn part2(&self, _: &Self::Parsed) -> Result<PartResult, DayError> {
+            Ok(PartResult::new(2, ""))
+        }
+    }
+
+    #[test]
+    fn test_checkpoint() {
+        assert_eq!(checkpoint(Progress::count(1, "steps")), Ok(()));
+
+        let watch = Watch::new(None);
+        let token = watch.token();
+        let stopped = watch.run(|| {
+            checkpoint(Progress::count(1, "steps"))?;
+            token.cancel();
+            checkpoint(Progress::count(2, "steps"))
+        });
+        assert_eq!(stopped.unwrap_err().reason, "cancelled");
+
+        let err = Watch::new(Some(Duration::from_millis(5))).run(|| loop {
+            if let Err(e) = checkpoint(Progress::of(1, 2, "steps")) {
+                break e;
+            }
+        });
+        assert!(err.timed_out);
+        assert_eq!(err.to_string(), "Input: timed out after 5ms");
+    }
+
+    #[test]
+    fn test_progress() {
+        let seen = Arc::new(Mutex::new(vec![]));
+        let record = Arc::clone(&seen);
+        Watch::new(None).on_progress(move |p| record.lock().unwrap().push(p.to_string())).run(|| {
+            checkpoint(Progress::of(1, 3, "scanners aligned")).unwrap();
+            thread::sleep(PROGRESS_EVERY);
+            checkpoint(Progress::of(2, 3, "scanners aligned")).unwrap();
+        });
+        assert_eq!(*seen.lock().unwrap(), vec!["2 of 3 scanners aligned"]);
+    }
+
+    #[test]
+    fn test_give_up() {
+        // Days that never check in are given up on when their time is up, not when they finish.
+        let started = Instant::now();
+        let err = run_day_watched(4, &Stubborn, &InputSource::Bundled, Watch::new(Some(Duration::from_millis(10))));
+        assert!(started.elapsed() < Duration::from_millis(500));
+        assert!(err.err().is_some_and(|e| e.timed_out && e.day == Some(4)));
+
+        let running = start_day(4, &Stubborn, &InputSource::Bundled, Watch::new(None));
+        assert!(running.poll(Duration::ZERO).is_none());
+        assert_eq!(running.cancel().to_string(), "Day 4: cancelled");
+        assert!(started.elapsed() < Duration::from_millis(500));
+
+        // Unless the caller is going on to solve something else.
+        let started = Instant::now();
+        let err = run_day_limited(4, &Stubborn, &InputSource::Bundled, Some(Duration::from_millis(10)));
+        assert!(started.elapsed() >= Duration::from_secs(1));
+        assert!(err.err().is_some_and(|e| e.timed_out));
+    }
+}